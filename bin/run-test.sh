@@ -16,7 +16,14 @@ fi
 cargo build --bin birb-cli
 
 for x in "$@"; do
-  target/debug/birb-cli "$x/inp.txt" 1>out.tmp || true
+  if [ -f "$x/birb.toml" ]; then
+    target/debug/birb-cli run "$x" 1>out.tmp || true
+  elif [ -f "$x/args.txt" ]; then
+    # shellcheck disable=SC2046
+    target/debug/birb-cli "$x/inp.txt" -- $(cat "$x/args.txt") 1>out.tmp || true
+  else
+    target/debug/birb-cli "$x/inp.txt" 1>out.tmp || true
+  fi
   if ! diff "$x/out.txt" out.tmp; then
     panic "$x: fail"
   fi
@@ -0,0 +1,84 @@
+//! Remembering which packages were checked, so that they are not checked again until they or the
+//! packages they depend on change. The fingerprints of the checked packages are kept in
+//! `target/birb/checked` in the directory of the main package, as `<name> <fingerprint>` lines.
+
+#[cfg(test)]
+mod tests;
+
+use birb_core::ident::Ident;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Returns the fingerprint of the package in the directory with the main file, given the
+/// fingerprints of the packages it depends on. The fingerprint changes when the manifest or any
+/// source file which the main file might import changes, or when this implementation does.
+pub fn fingerprint(dir: &Path, main: &Path, deps: &[u64]) -> Result<u64, String> {
+  let mut files = vec![dir.join(crate::manifest::FILE_NAME)];
+  sources(main.parent().unwrap_or(dir), &mut files)?;
+  files.sort();
+  let mut hasher = DefaultHasher::new();
+  env!("CARGO_PKG_VERSION").hash(&mut hasher);
+  deps.hash(&mut hasher);
+  for file in files {
+    let bs = std::fs::read(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    file.strip_prefix(dir).unwrap_or(&file).hash(&mut hasher);
+    bs.hash(&mut hasher);
+  }
+  Ok(hasher.finish())
+}
+
+/// Adds the paths of the source files in the directory and its subdirectories, except `target`.
+fn sources(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+  let err = |e: std::io::Error| format!("{}: {}", dir.display(), e);
+  for entry in std::fs::read_dir(dir).map_err(err)? {
+    let path = entry.map_err(err)?.path();
+    if path.is_dir() {
+      if !path.ends_with("target") {
+        sources(&path, files)?;
+      }
+    } else if path.extension().is_some_and(|x| x == "birb") {
+      files.push(path);
+    }
+  }
+  Ok(())
+}
+
+fn path(dir: &Path) -> PathBuf {
+  dir.join("target").join("birb").join("checked")
+}
+
+/// Returns the packages which have the same fingerprints as when the package in the directory was
+/// last checked.
+pub fn checked(dir: &Path, fingerprints: &HashMap<Ident, u64>) -> HashSet<Ident> {
+  // a missing or broken cache just means checking everything again.
+  let s = match std::fs::read_to_string(path(dir)) {
+    Ok(s) => s,
+    Err(_) => return HashSet::new(),
+  };
+  s.lines()
+    .filter_map(|line| {
+      let (name, fp) = line.split_once(' ')?;
+      let name = Ident::new(name);
+      let fp = u64::from_str_radix(fp, 16).ok()?;
+      (fingerprints.get(&name) == Some(&fp)).then_some(name)
+    })
+    .collect()
+}
+
+/// Remembers that the package in the directory was checked with its dependencies having the
+/// fingerprints.
+pub fn save(dir: &Path, fingerprints: &HashMap<Ident, u64>) -> Result<(), String> {
+  let path = path(dir);
+  let err = |e: std::io::Error| format!("{}: {}", path.display(), e);
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent).map_err(err)?;
+  }
+  let mut lines: Vec<_> = fingerprints
+    .iter()
+    .map(|(name, fp)| format!("{} {:016x}\n", name, fp))
+    .collect();
+  lines.sort();
+  std::fs::write(&path, lines.concat()).map_err(err)
+}
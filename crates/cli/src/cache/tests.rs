@@ -0,0 +1,72 @@
+use super::{checked, fingerprint, save};
+use birb_core::ident::Ident;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Makes a fresh package directory for the test with a manifest and some modules.
+fn package(test: &str) -> PathBuf {
+  let dir = std::env::temp_dir().join(format!("birb-cache-{}-{}", std::process::id(), test));
+  let _ = std::fs::remove_dir_all(&dir);
+  std::fs::create_dir_all(dir.join("shapes")).unwrap();
+  write(&dir, "birb.toml", "[package]\nname = \"geo\"\n");
+  write(&dir, "main.birb", "import shapes::square;\n");
+  write(&dir, "shapes/square.birb", "pub fn side(): Nat { 3 }\n");
+  dir
+}
+
+fn write(dir: &Path, file: &str, contents: &str) {
+  std::fs::write(dir.join(file), contents).unwrap();
+}
+
+fn fp(dir: &Path, deps: &[u64]) -> u64 {
+  fingerprint(dir, &dir.join("main.birb"), deps).unwrap()
+}
+
+#[test]
+fn fingerprint_same() {
+  let dir = package("same");
+  assert_eq!(fp(&dir, &[1]), fp(&dir, &[1]));
+}
+
+#[test]
+fn fingerprint_changes() {
+  let dir = package("changes");
+  let old = fp(&dir, &[]);
+  write(&dir, "shapes/square.birb", "pub fn side(): Nat { 4 }\n");
+  let new = fp(&dir, &[]);
+  assert_ne!(old, new);
+  write(
+    &dir,
+    "birb.toml",
+    "[package]\nname = \"geo\"\n[lints]\nunused-fn = \"allow\"\n",
+  );
+  assert_ne!(new, fp(&dir, &[]));
+}
+
+#[test]
+fn fingerprint_deps_change() {
+  let dir = package("deps-change");
+  assert_ne!(fp(&dir, &[1]), fp(&dir, &[2]));
+}
+
+#[test]
+fn fingerprint_ignores_target() {
+  let dir = package("ignores-target");
+  let old = fp(&dir, &[]);
+  std::fs::create_dir_all(dir.join("target")).unwrap();
+  write(&dir, "target/old.birb", "fn main() {}\n");
+  assert_eq!(old, fp(&dir, &[]));
+}
+
+#[test]
+fn save_then_checked() {
+  let dir = package("save");
+  let geo = Ident::new("geo");
+  let num = Ident::new("num");
+  assert!(checked(&dir, &HashMap::new()).is_empty());
+  let fps: HashMap<_, _> = [(geo.clone(), 1), (num.clone(), 2)].into_iter().collect();
+  save(&dir, &fps).unwrap();
+  assert_eq!(checked(&dir, &fps), fps.keys().cloned().collect());
+  let changed: HashMap<_, _> = [(geo.clone(), 1), (num, 3)].into_iter().collect();
+  assert_eq!(checked(&dir, &changed), [geo].into_iter().collect());
+}
@@ -1,16 +1,113 @@
-fn run() -> Result<birb_core::interpret::Value, Box<dyn std::error::Error>> {
-  let file = match std::env::args().nth(1) {
+mod cache;
+mod manifest;
+mod package;
+
+use birb_core::warning::{Level, Levels, Lint};
+
+const USAGE: &str = "usage:
+  birb-cli <file> [<options>] [-- <args>...]
+  birb-cli check <file> [<options>]
+  birb-cli build [<dir>] [<options>]
+  birb-cli run [<dir>] [<options>] [-- <args>...]
+
+options:
+  --allow <lint>  don't warn for the lint
+  --deny <lint>   make warnings for the lint errors
+  --show-effects  show the effects inferred for fns without `affects`";
+
+fn run() -> Result<Option<birb_core::interpret::Value>, Box<dyn std::error::Error>> {
+  let mut args = std::env::args().skip(1).peekable();
+  let cmd = match args.next() {
     Some(x) => x,
-    None => return Err("give exactly 1 argument (a filename)".into()),
+    None => return Err(USAGE.into()),
   };
-  let bs = std::fs::read(&file)?;
-  let res = birb_core::get(&bs)?;
-  Ok(res)
+  // the package commands take an optional directory, which defaults to the current one. the other
+  // commands take a file.
+  let path = match cmd.as_str() {
+    "build" | "run" => match args.next_if(|x| !x.starts_with("--")) {
+      Some(x) => x,
+      None => ".".to_owned(),
+    },
+    "check" => match args.next() {
+      Some(x) => x,
+      None => return Err(USAGE.into()),
+    },
+    _ => cmd.clone(),
+  };
+  let mut levels = Levels::new();
+  let mut show_effects = false;
+  let is_opt = |x: &String| x == "--allow" || x == "--deny" || x == "--show-effects";
+  while let Some(opt) = args.next_if(is_opt) {
+    let level = match opt.as_str() {
+      "--allow" => Level::Allow,
+      "--deny" => Level::Deny,
+      _ => {
+        show_effects = true;
+        continue;
+      }
+    };
+    let name = match args.next() {
+      Some(x) => x,
+      None => return Err(USAGE.into()),
+    };
+    match Lint::from_name(&name) {
+      Some(lint) => levels.insert(lint, level),
+      None => return Err(format!("unknown lint: {}", name).into()),
+    };
+  }
+  let prog_args: Vec<_> = match args.next() {
+    None => Vec::new(),
+    Some(x) if x == "--" && cmd != "build" && cmd != "check" => args.collect(),
+    Some(x) => return Err(format!("unexpected argument: {} (use -- to pass arguments)", x).into()),
+  };
+  // the directory and dependency fingerprints of the package, if any.
+  let mut pkg_cache = None;
+  let program = match cmd.as_str() {
+    "build" | "run" => {
+      let pkg = package::get(std::path::Path::new(&path))?;
+      // the options take precedence over the manifest.
+      for (lint, level) in pkg.lints {
+        levels.entry(lint).or_insert(level);
+      }
+      let mut program = birb_core::module::get_path(&pkg.main, &pkg.packages)?;
+      program.checked = cache::checked(&pkg.dir, &pkg.fingerprints);
+      pkg_cache = Some((pkg.dir, pkg.fingerprints));
+      program
+    }
+    _ => birb_core::module::get_path(std::path::Path::new(&path), &Default::default())?,
+  };
+  let (top_defns, info) = birb_core::check(program, &levels)?;
+  for w in info.warnings {
+    println!("warning: {}", w);
+  }
+  if let Some((dir, fingerprints)) = &pkg_cache {
+    cache::save(dir, fingerprints)?;
+  }
+  if show_effects {
+    // the effects of the deps are not inferred when they were checked before, so show only the
+    // effects of the package's own fns.
+    let is_dep = |name: &birb_core::ident::Ident| match (name.split_first(), &pkg_cache) {
+      (Some((package, _)), Some((_, fingerprints))) => fingerprints.contains_key(&package),
+      _ => false,
+    };
+    for (name, effects) in info.effects.into_iter().filter(|(name, _)| !is_dep(name)) {
+      println!("{} affects {}", name, effects);
+    }
+  }
+  if cmd == "build" || cmd == "check" {
+    return Ok(None);
+  }
+  let res = birb_core::run(top_defns, &prog_args)?;
+  Ok(Some(res))
 }
 
 fn main() {
   match run() {
-    Ok(v) => println!("{}", v),
+    Ok(None) => {}
+    Ok(Some(v)) => {
+      println!("{}", v);
+      std::process::exit(birb_core::interpret::exit_code(&v))
+    }
     Err(e) => {
       println!("error: {}", e);
       std::process::exit(1)
@@ -0,0 +1,145 @@
+//! Package manifests, in `birb.toml` files. Only a small subset of TOML is supported:
+//!
+//! ```toml
+//! [package]
+//! name = "app"
+//! main = "main.birb"
+//!
+//! [dependencies]
+//! geo = { path = "../geo" }
+//!
+//! [lints]
+//! unused-var = "allow"
+//! ```
+//!
+//! `main` is optional, and defaults to `main.birb`. Each lint may be `"allow"`, `"warn"` or
+//! `"deny"`.
+
+#[cfg(test)]
+mod tests;
+
+use birb_core::warning::{Level, Levels, Lint};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The name of a manifest file.
+pub const FILE_NAME: &str = "birb.toml";
+
+/// A package manifest.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Manifest {
+  /// The name of the package, which is how other packages import it.
+  pub name: String,
+  /// The path to the main file, relative to the manifest.
+  pub main: PathBuf,
+  /// The dependencies, by name, with their paths relative to the manifest.
+  pub deps: Vec<(String, PathBuf)>,
+  /// The levels of the lints for this package.
+  pub lints: Levels,
+}
+
+/// Parse a manifest.
+pub fn get(s: &str) -> Result<Manifest, String> {
+  let mut section = None::<String>;
+  let mut name = None;
+  let mut main = None;
+  let mut deps = Vec::new();
+  let mut lints = Levels::new();
+  for (idx, line) in s.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let err = |msg: &str| format!("line {}: {}", idx + 1, msg);
+    if let Some(rest) = line.strip_prefix('[') {
+      match rest.strip_suffix(']') {
+        Some(x) => section = Some(x.trim().to_owned()),
+        None => return Err(err("unclosed section header")),
+      }
+      continue;
+    }
+    let (key, val) = match line.split_once('=') {
+      Some((k, v)) => (k.trim(), v.trim()),
+      None => return Err(err("expected `key = value`")),
+    };
+    match section.as_deref() {
+      Some("package") => {
+        let val = string(val).ok_or_else(|| err("expected a string"))?;
+        match key {
+          "name" => name = Some(val.to_owned()),
+          "main" => main = Some(PathBuf::from(val)),
+          _ => return Err(err(&format!("unknown key: {}", key))),
+        }
+      }
+      Some("dependencies") => {
+        let path = table(val)
+          .and_then(|fields| fields.get("path").copied())
+          .ok_or_else(|| err("expected `{ path = \"...\" }`"))?;
+        if deps.iter().any(|(n, _)| n == key) {
+          return Err(err(&format!("duplicate dependency: {}", key)));
+        }
+        deps.push((key.to_owned(), PathBuf::from(path)));
+      }
+      Some("lints") => {
+        let lint = Lint::from_name(key).ok_or_else(|| err(&format!("unknown lint: {}", key)))?;
+        let level = match string(val) {
+          Some("allow") => Level::Allow,
+          Some("warn") => Level::Warn,
+          Some("deny") => Level::Deny,
+          _ => return Err(err("expected \"allow\", \"warn\" or \"deny\"")),
+        };
+        lints.insert(lint, level);
+      }
+      Some(x) => return Err(err(&format!("unknown section: {}", x))),
+      None => return Err(err("expected a section header")),
+    }
+  }
+  let name = name.ok_or("missing package name")?;
+  if !is_package_name(&name) {
+    return Err(format!("invalid package name: {}", name));
+  }
+  for (dep, _) in deps.iter() {
+    if !is_package_name(dep) {
+      return Err(format!("invalid package name: {}", dep));
+    }
+  }
+  Ok(Manifest {
+    name,
+    main: main.unwrap_or_else(|| PathBuf::from("main.birb")),
+    deps,
+    lints,
+  })
+}
+
+/// Read and parse the manifest in the given directory.
+pub fn read(dir: &Path) -> Result<Manifest, String> {
+  let path = dir.join(FILE_NAME);
+  let s = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+  get(&s).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Package names must be usable in imports, like `import geo;`.
+fn is_package_name(s: &str) -> bool {
+  let mut cs = s.chars();
+  cs.next().is_some_and(|c| c.is_ascii_lowercase())
+    && cs.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn string(s: &str) -> Option<&str> {
+  let s = s.strip_prefix('"')?.strip_suffix('"')?;
+  if s.contains('"') {
+    None
+  } else {
+    Some(s)
+  }
+}
+
+fn table(s: &str) -> Option<HashMap<&str, &str>> {
+  let s = s.strip_prefix('{')?.strip_suffix('}')?;
+  let mut ret = HashMap::new();
+  for field in s.split(',') {
+    let (key, val) = field.split_once('=')?;
+    ret.insert(key.trim(), string(val.trim())?);
+  }
+  Some(ret)
+}
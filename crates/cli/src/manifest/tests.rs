@@ -0,0 +1,129 @@
+use super::{get, Level, Lint, Manifest};
+use std::path::PathBuf;
+
+#[test]
+fn full() {
+  let s = r#"
+# the app
+[package]
+name = "app"
+main = "src/app.birb"
+
+[dependencies]
+geo = { path = "../geo" }
+num_2 = { path = "num" }
+
+[lints]
+unused-var = "allow"
+shadowing = "deny"
+"#;
+  assert_eq!(
+    get(s).unwrap(),
+    Manifest {
+      name: "app".to_owned(),
+      main: PathBuf::from("src/app.birb"),
+      deps: vec![
+        ("geo".to_owned(), PathBuf::from("../geo")),
+        ("num_2".to_owned(), PathBuf::from("num")),
+      ],
+      lints: [
+        (Lint::UnusedVar, Level::Allow),
+        (Lint::Shadowing, Level::Deny)
+      ]
+      .into_iter()
+      .collect(),
+    }
+  );
+}
+
+#[test]
+fn default_main() {
+  let man = get("[package]\nname = \"app\"\n").unwrap();
+  assert_eq!(man.main, PathBuf::from("main.birb"));
+  assert!(man.deps.is_empty());
+  assert!(man.lints.is_empty());
+}
+
+#[test]
+fn unknown_package_key() {
+  assert_eq!(
+    get("[package]\nname = \"app\"\nversion = \"1.0\"\n").unwrap_err(),
+    "line 3: unknown key: version"
+  );
+}
+
+#[test]
+fn unknown_section() {
+  assert_eq!(
+    get("[package]\nname = \"app\"\n[features]\nfast = \"yes\"\n").unwrap_err(),
+    "line 4: unknown section: features"
+  );
+}
+
+#[test]
+fn unknown_lint() {
+  assert_eq!(
+    get("[package]\nname = \"app\"\n[lints]\nunused-thing = \"allow\"\n").unwrap_err(),
+    "line 4: unknown lint: unused-thing"
+  );
+}
+
+#[test]
+fn not_a_string() {
+  assert_eq!(
+    get("[package]\nname = app\n").unwrap_err(),
+    "line 2: expected a string"
+  );
+}
+
+#[test]
+fn bad_lint_level() {
+  assert_eq!(
+    get("[package]\nname = \"app\"\n[lints]\nunused-var = \"forbid\"\n").unwrap_err(),
+    "line 4: expected \"allow\", \"warn\" or \"deny\""
+  );
+}
+
+#[test]
+fn bad_dependency() {
+  assert_eq!(
+    get("[package]\nname = \"app\"\n[dependencies]\ngeo = \"../geo\"\n").unwrap_err(),
+    "line 4: expected `{ path = \"...\" }`"
+  );
+}
+
+#[test]
+fn duplicate_dependency() {
+  let s =
+    "[package]\nname = \"app\"\n[dependencies]\ngeo = { path = \"a\" }\ngeo = { path = \"b\" }\n";
+  assert_eq!(get(s).unwrap_err(), "line 5: duplicate dependency: geo");
+}
+
+#[test]
+fn invalid_name() {
+  assert_eq!(
+    get("[package]\nname = \"App\"\n").unwrap_err(),
+    "invalid package name: App"
+  );
+}
+
+#[test]
+fn missing_package() {
+  assert_eq!(
+    get("[dependencies]\ngeo = { path = \"geo\" }\n").unwrap_err(),
+    "missing package name"
+  );
+}
+
+#[test]
+fn empty() {
+  assert_eq!(get("").unwrap_err(), "missing package name");
+}
+
+#[test]
+fn no_section() {
+  assert_eq!(
+    get("name = \"app\"\n").unwrap_err(),
+    "line 1: expected a section header"
+  );
+}
@@ -0,0 +1,116 @@
+//! Resolving the packages a package depends on.
+
+use crate::{cache, manifest};
+use birb_core::ident::Ident;
+use birb_core::module::Packages;
+use birb_core::warning::Levels;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A package with all of its dependencies resolved.
+#[derive(Debug)]
+pub struct Package {
+  /// The directory of the package.
+  pub dir: PathBuf,
+  /// The path to the main file.
+  pub main: PathBuf,
+  /// Every package this depends on, directly or not.
+  pub packages: Packages,
+  /// The fingerprints of every package this depends on, directly or not.
+  pub fingerprints: HashMap<Ident, u64>,
+  /// The levels of the lints, from the manifest of this package only.
+  pub lints: Levels,
+}
+
+/// Resolve the package in the given directory. Dependencies are found by their paths, so this never
+/// uses the network.
+pub fn get(dir: &Path) -> Result<Package, String> {
+  let man = manifest::read(dir)?;
+  let mut rs = Resolver {
+    dirs: HashMap::new(),
+    stack: vec![man.name.clone()],
+    packages: Packages::default(),
+    fingerprints: HashMap::new(),
+  };
+  rs.packages.main_deps = dep_names(&man);
+  let root = canonical(dir)?;
+  rs.dirs.insert(man.name.clone(), root.clone());
+  rs.deps_of(&root, &man)?;
+  Ok(Package {
+    dir: root,
+    main: dir.join(&man.main),
+    packages: rs.packages,
+    fingerprints: rs.fingerprints,
+    lints: man.lints,
+  })
+}
+
+struct Resolver {
+  /// The directories of the packages seen so far, by name.
+  dirs: HashMap<String, PathBuf>,
+  /// The packages currently being resolved, used to detect cycles.
+  stack: Vec<String>,
+  packages: Packages,
+  fingerprints: HashMap<Ident, u64>,
+}
+
+impl Resolver {
+  fn deps_of(&mut self, dir: &Path, man: &manifest::Manifest) -> Result<(), String> {
+    for (name, path) in man.deps.iter() {
+      let dep_dir = canonical(&dir.join(path))?;
+      if let Some(old) = self.dirs.get(name) {
+        if *old != dep_dir {
+          return Err(format!(
+            "conflicting packages named {}: {} and {}",
+            name,
+            old.display(),
+            dep_dir.display()
+          ));
+        }
+        if let Some(idx) = self.stack.iter().position(|x| x == name) {
+          let mut cycle = self.stack[idx..].to_vec();
+          cycle.push(name.clone());
+          return Err(format!("package cycle: {}", cycle.join(" -> ")));
+        }
+        continue;
+      }
+      let dep_man = manifest::read(&dep_dir)?;
+      if dep_man.name != *name {
+        return Err(format!(
+          "{}: expected package {}, found {}",
+          dep_dir.join(manifest::FILE_NAME).display(),
+          name,
+          dep_man.name
+        ));
+      }
+      self.dirs.insert(name.clone(), dep_dir.clone());
+      let ident = Ident::new(name);
+      let main = dep_dir.join(&dep_man.main);
+      let dep_names = dep_names(&dep_man);
+      self.packages.deps.insert(ident.clone(), dep_names);
+      self.packages.paths.insert(ident.clone(), main.clone());
+      self.stack.push(name.clone());
+      self.deps_of(&dep_dir, &dep_man)?;
+      self.stack.pop();
+      // the deps of the dep were all resolved above, so they have fingerprints.
+      let dep_fps: Vec<_> = dep_man
+        .deps
+        .iter()
+        .map(|(d, _)| self.fingerprints[&Ident::new(d)])
+        .collect();
+      let fp = cache::fingerprint(&dep_dir, &main, &dep_fps)?;
+      self.fingerprints.insert(ident, fp);
+    }
+    Ok(())
+  }
+}
+
+fn dep_names(man: &manifest::Manifest) -> HashSet<Ident> {
+  man.deps.iter().map(|(name, _)| Ident::new(name)).collect()
+}
+
+fn canonical(dir: &Path) -> Result<PathBuf, String> {
+  dir
+    .canonicalize()
+    .map_err(|e| format!("{}: {}", dir.display(), e))
+}
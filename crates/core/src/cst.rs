@@ -11,8 +11,17 @@ pub enum TopDefn {
   Struct(StructDefn),
   /// An enum (sum type) definition.
   Enum(EnumDefn),
+  /// A type definition.
+  Type(TypeDefn),
   /// A function definition.
   Fn_(Box<FnDefn>),
+  /// A trait definition.
+  Trait(TraitDefn),
+  /// An impl of a trait for a type.
+  Impl(ImplDefn),
+  /// An import of another file, like `import lib::strings;`. These are removed when loading the
+  /// modules of a program.
+  Import(Vec<Ident>),
 }
 
 /// A pair of identifier and type. Sometimes used for function parameters, but also re-used for
@@ -30,8 +39,11 @@ pub struct Param<I, T> {
 pub struct StructDefn {
   /// The name.
   pub name: Ident,
+  /// Whether this is visible outside its module, like `pub fn`.
+  pub pub_: bool,
   /// The generic type/effect parameters. Will be empty iff no params were written in the source.
-  pub params: Vec<Param<Ident, Kind>>,
+  /// The kinds are None if not written, in which case they are inferred.
+  pub params: Vec<Param<Ident, Option<Kind>>>,
   /// The fields.
   pub fields: Vec<Param<Ident, Kinded>>,
 }
@@ -41,10 +53,37 @@ pub struct StructDefn {
 pub struct EnumDefn {
   /// The name.
   pub name: Ident,
+  /// Whether this is visible outside its module, like `pub fn`.
+  pub pub_: bool,
   /// The generic type/effect parameters. Will be empty iff no params were written in the source.
-  pub params: Vec<Param<Ident, Kind>>,
-  /// The constructors (also called variants) of the enum.
-  pub ctors: Vec<Param<Ident, Kinded>>,
+  /// The kinds are None if not written, in which case they are inferred.
+  pub params: Vec<Param<Ident, Option<Kind>>>,
+  /// The constructors (also called variants) of the enum, with their payloads.
+  pub ctors: Vec<Param<Ident, Payload>>,
+}
+
+/// The payload of an enum constructor.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Payload {
+  /// Positional payloads, like `pair(Nat, Str)`. Empty for a unit constructor, like `none`.
+  Positional(Vec<Kinded>),
+  /// Named fields, like `circle { radius: Nat }`.
+  Named(Vec<Param<Ident, Kinded>>),
+}
+
+/// A type definition. Either an alias, like `type Io = {Stdin, Stdout};`, or intrinsic, like
+/// `type Nat;`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TypeDefn {
+  /// The name.
+  pub name: Ident,
+  /// Whether this is visible outside its module, like `pub fn`.
+  pub pub_: bool,
+  /// The generic type/effect parameters. Will be empty iff no params were written in the source.
+  /// The kinds are None if not written, in which case they are inferred.
+  pub params: Vec<Param<Ident, Option<Kind>>>,
+  /// What this is an alias for. None iff this is intrinsic.
+  pub def: Option<Kinded>,
 }
 
 /// A function definition.
@@ -52,8 +91,13 @@ pub struct EnumDefn {
 pub struct FnDefn {
   /// The name.
   pub name: Ident,
+  /// Whether this is visible outside its module, like `pub fn`.
+  pub pub_: bool,
   /// The generic type/effect parameters. Will be empty iff no params were written in the source.
-  pub big_params: Vec<Param<Ident, Kind>>,
+  /// The kinds are None if not written, in which case they are inferred.
+  pub big_params: Vec<Param<Ident, Option<Kind>>>,
+  /// The traits which the big params must implement, like `T: Eq` for `[T: Type + Eq]`.
+  pub bounds: Vec<Param<Ident, Ident>>,
   /// The value parameters.
   pub params: Vec<Param<Ident, Kinded>>,
   /// The return type. Even functions which 'return nothing' have one.
@@ -62,8 +106,36 @@ pub struct FnDefn {
   pub requires: Option<Expr>,
   /// The ensures annotation, the post-condition just after the function returns.
   pub ensures: Option<Expr>,
-  /// The body.
-  pub body: Block,
+  /// The body. Will be None iff this is an intrinsic, written with `;` in place of the body.
+  pub body: Option<Block>,
+}
+
+/// A trait definition, like `trait Show { fn show(x: Self): Str; }`. `Self` is the type which
+/// implements the trait.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TraitDefn {
+  /// The name.
+  pub name: Ident,
+  /// Whether this is visible outside its module, like `pub fn`.
+  pub pub_: bool,
+  /// The functions, with no bodies. Each takes `Self` as its first param.
+  pub fns: Vec<FnDefn>,
+}
+
+/// An impl of a trait, like `impl[T: Type + Show] Show for Option[T] { ... }`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImplDefn {
+  /// The generic type/effect parameters. Will be empty iff no params were written in the source.
+  /// The kinds are None if not written, in which case they are inferred.
+  pub big_params: Vec<Param<Ident, Option<Kind>>>,
+  /// The traits which the big params must implement.
+  pub bounds: Vec<Param<Ident, Ident>>,
+  /// The trait.
+  pub trait_: Ident,
+  /// The type implementing the trait, which must be a named type applied to the big params.
+  pub type_: Kinded,
+  /// The functions, one for each function of the trait.
+  pub fns: Vec<FnDefn>,
 }
 
 /// A kind. The most common kind is Type, but we also have Effect. We also have arrow kinds (like
@@ -104,6 +176,9 @@ pub enum Kinded {
   Arrow(Box<Kinded>, Box<Kinded>),
   /// An effectful kind. The left Kinded is a Type and the right is an Effect.
   Effectful(Box<Kinded>, Box<Kinded>),
+  /// The type of expressions which never produce a value, like `return x`. It cannot be written in
+  /// the source.
+  Never,
 }
 
 impl fmt::Display for Kinded {
@@ -120,6 +195,7 @@ impl fmt::Display for Kinded {
       Self::Set(ts) => SliceDisplay::new("{", ts, "}").fmt(f),
       Self::Arrow(k1, k2) => write!(f, "({}) -> ({})", k1, k2),
       Self::Effectful(t, e) => write!(f, "({}) affects ({})", t, e),
+      Self::Never => write!(f, "!"),
     }
   }
 }
@@ -133,8 +209,11 @@ pub enum Expr {
   Number(u64),
   /// A tuple, like `(1, "e")`.
   Tuple(Vec<Expr>),
-  /// A struct expression, like `Foo { x: 3 }`.
-  Struct(Ident, Vec<Kinded>, Vec<Field<Expr>>),
+  /// A list, like `[1, 2, 3]`.
+  List(Vec<Expr>),
+  /// A struct expression, like `Foo { x: 3 }`. The optional expression is the struct to take the
+  /// rest of the fields from, like `Foo { x: 3, ..other }`.
+  Struct(Ident, Vec<Kinded>, Vec<Field<Expr>>, Option<Box<Expr>>),
   /// An identifier, like `a`.
   Ident(Ident),
   /// A function call, like `f(x)`.
@@ -143,10 +222,50 @@ pub enum Expr {
   FieldGet(Box<Expr>, Ident),
   /// A function call written like a method call, like `x.f()`. Semantically equivalent to `f(x)`.
   MethodCall(Box<Expr>, Ident, Vec<Kinded>, Vec<Expr>),
+  /// A binary operator expression, like `x + 1`.
+  BinOp(Box<Expr>, BinOp, Box<Expr>),
+  /// A boolean negation, like `!x`.
+  Not(Box<Expr>),
   /// A match expression, like `match x { 3 { 4 } _ { 5 } }`.
   Match(Box<Expr>, Vec<Arm>),
+  /// An if expression, like `if x { 3 } else { 4 }`. If no else was written in the source, the else
+  /// block is `{ () }`. An `else if` is represented as an else block containing only an if.
+  If(Box<Expr>, Box<Block>, Box<Block>),
   /// A block, like `{ let x = 3; x.add(4) }`.
   Block(Box<Block>),
+  /// A return from the enclosing function, like `return 3`.
+  Return(Box<Expr>),
+}
+
+/// A binary operator. Each one is shorthand for a function from the standard library.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[allow(missing_docs)]
+pub enum BinOp {
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Eq,
+  Neq,
+  Lt,
+  Gt,
+  Le,
+  Ge,
+  And,
+  Or,
+}
+
+impl BinOp {
+  /// The precedence. Higher binds tighter.
+  pub fn prec(&self) -> usize {
+    match self {
+      Self::Or => 0,
+      Self::And => 1,
+      Self::Eq | Self::Neq | Self::Lt | Self::Gt | Self::Le | Self::Ge => 2,
+      Self::Add | Self::Sub => 3,
+      Self::Mul | Self::Div => 4,
+    }
+  }
 }
 
 /// An arm of a match expression.
@@ -154,6 +273,8 @@ pub enum Expr {
 pub struct Arm {
   /// The pattern to match on.
   pub pat: Pat,
+  /// The guard, like `if x > 3`, which must also be true for the arm to be taken.
+  pub guard: Option<Expr>,
   /// The block evaluated if the match succeeds.
   pub block: Block,
 }
@@ -169,14 +290,24 @@ pub enum Pat {
   Number(u64),
   /// A tuple, like `(4, x)`.
   Tuple(Vec<Pat>),
-  /// A constructor pattern, like `some(x)`.
-  Ctor(Ident, Box<Pat>),
+  /// A list, like `[]`, `[x, 3]` or `[x, ..rest]`. The optional pattern matches the rest of the
+  /// list.
+  List(Vec<Pat>, Option<Box<Pat>>),
+  /// A constructor pattern, like `some(x)` or `pair(x, y)`. Has no pats for a unit constructor.
+  Ctor(Ident, Vec<Pat>),
   /// An identifier pattern, like `x`.
   Ident(Ident),
+  /// A struct pattern, like `Foo { x, y: 3 }`. The bool is whether the rest of the fields may be
+  /// omitted, like `Foo { x, .. }`.
+  Struct(Ident, Vec<Field<Pat>>, bool),
+  /// An or-pattern, like `1 | 2`. Every alternative must bind the same variables.
+  Or(Vec<Pat>),
+  /// An as-binding, like `x @ some(_)`, which binds the whole value while also matching it.
+  At(Ident, Box<Pat>),
 }
 
 /// A field.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum Field<T> {
   /// An identifier. Shorthand for `x: x`.
   Ident(Ident),
@@ -1,75 +1,304 @@
-//! Desugaring. AKA 77 lines just to get rid of MethodCall.
+//! Desugaring. Gets rid of MethodCall, BinOp, Not, and If, turns `true` and `false` into calls and
+//! patterns of the `Bool` constructors, qualified so that they never mean another enum's
+//! constructors, and turns the names of unit constructors, like `none`, into constructor calls and
+//! patterns. Params and the names bound by let are vars even when they have the name of a unit
+//! constructor, and they hide it while in scope.
 
-use crate::cst::{Arm, Block, Expr, Field, FnDefn, Stmt, TopDefn};
+use crate::cst::{Arm, BinOp, Block, Expr, Field, FnDefn, Param, Pat, Payload, Stmt, TopDefn};
+use crate::ident::Ident;
+use crate::std_lib as birb_std_lib;
+use std::collections::HashSet;
+
+struct Cx {
+  /// The names of the unit constructors, both unqualified and qualified by their enum.
+  units: HashSet<Ident>,
+  /// The vars in scope, which hide the unit constructors with the same names.
+  vars: Vec<Ident>,
+}
+
+impl Cx {
+  fn is_unit(&self, id: &Ident) -> bool {
+    self.units.contains(id) && !self.vars.contains(id)
+  }
+}
 
 /// Does the conversion.
 pub fn get(top_defns: Vec<TopDefn>) -> Vec<TopDefn> {
-  top_defns.into_iter().map(get_top_defn).collect()
+  let mut units = HashSet::new();
+  for td in top_defns.iter() {
+    if let TopDefn::Enum(enum_) = td {
+      let is_unit = |p: &&Param<Ident, Payload>| p.type_ == Payload::Positional(vec![]);
+      for p in enum_.ctors.iter().filter(is_unit) {
+        units.insert(p.ident.clone());
+        units.insert(Ident::qualified(&enum_.name, &p.ident));
+      }
+    }
+  }
+  let mut cx = Cx {
+    units,
+    vars: Vec::new(),
+  };
+  top_defns
+    .into_iter()
+    .map(|td| get_top_defn(&mut cx, td))
+    .collect()
 }
 
-fn get_top_defn(top_defn: TopDefn) -> TopDefn {
+fn get_top_defn(cx: &mut Cx, top_defn: TopDefn) -> TopDefn {
   match top_defn {
-    TopDefn::Struct(..) | TopDefn::Enum(..) => top_defn,
-    TopDefn::Fn_(fn_) => TopDefn::Fn_(get_fn(*fn_).into()),
+    TopDefn::Struct(..)
+    | TopDefn::Enum(..)
+    | TopDefn::Type(..)
+    | TopDefn::Trait(..)
+    | TopDefn::Import(..) => top_defn,
+    TopDefn::Fn_(fn_) => TopDefn::Fn_(get_fn(cx, *fn_).into()),
+    TopDefn::Impl(mut impl_) => {
+      impl_.fns = impl_.fns.into_iter().map(|f| get_fn(cx, f)).collect();
+      TopDefn::Impl(impl_)
+    }
   }
 }
 
-fn get_fn(func: FnDefn) -> FnDefn {
-  FnDefn {
-    requires: func.requires.map(get_expr),
-    ensures: func.ensures.map(get_expr),
-    body: get_block(func.body),
+fn get_fn(cx: &mut Cx, func: FnDefn) -> FnDefn {
+  cx.vars.extend(func.params.iter().map(|p| p.ident.clone()));
+  // the result is bound in the ensures.
+  cx.vars.push(Ident::new("ret"));
+  let ensures = func.ensures.map(|e| get_expr(cx, e));
+  cx.vars.pop();
+  let ret = FnDefn {
+    requires: func.requires.map(|e| get_expr(cx, e)),
+    ensures,
+    body: func.body.map(|b| get_block(cx, b)),
     ..func
-  }
+  };
+  cx.vars.clear();
+  ret
 }
 
-fn get_expr(expr: Expr) -> Expr {
+fn get_expr(cx: &mut Cx, expr: Expr) -> Expr {
   match expr {
+    Expr::Ident(ref id) if is_bool_lit(id) => bool_ctor(id),
+    Expr::Ident(id) if cx.is_unit(&id) => Expr::FnCall(id, vec![], vec![]),
     Expr::String_(..) | Expr::Number(..) | Expr::Ident(..) => expr,
-    Expr::Tuple(exprs) => Expr::Tuple(exprs.into_iter().map(get_expr).collect()),
-    Expr::Struct(name, args, fields) => {
-      Expr::Struct(name, args, fields.into_iter().map(get_field).collect())
-    }
-    Expr::FnCall(name, big_args, args) => {
-      Expr::FnCall(name, big_args, args.into_iter().map(get_expr).collect())
-    }
-    Expr::FieldGet(expr, field) => Expr::FieldGet(get_expr(*expr).into(), field),
+    Expr::Tuple(exprs) => Expr::Tuple(exprs.into_iter().map(|e| get_expr(cx, e)).collect()),
+    Expr::List(exprs) => Expr::List(exprs.into_iter().map(|e| get_expr(cx, e)).collect()),
+    Expr::Struct(name, args, fields, base) => Expr::Struct(
+      name,
+      args,
+      fields.into_iter().map(|f| get_field(cx, f)).collect(),
+      base.map(|e| get_expr(cx, *e).into()),
+    ),
+    Expr::FnCall(name, big_args, args) => Expr::FnCall(
+      name,
+      big_args,
+      args.into_iter().map(|e| get_expr(cx, e)).collect(),
+    ),
+    Expr::FieldGet(expr, field) => Expr::FieldGet(get_expr(cx, *expr).into(), field),
     Expr::MethodCall(recv, name, big_args, mut args) => {
-      // the one thing we _actually_ do: get rid of MethodCall
+      // one of the desugarings listed at the top: the receiver becomes the first arg.
       args.insert(0, *recv);
-      Expr::FnCall(name, big_args, args.into_iter().map(get_expr).collect())
+      Expr::FnCall(
+        name,
+        big_args,
+        args.into_iter().map(|e| get_expr(cx, e)).collect(),
+      )
     }
     Expr::Match(head, arms) => Expr::Match(
-      get_expr(*head).into(),
-      arms.into_iter().map(get_arm).collect(),
+      get_expr(cx, *head).into(),
+      arms.into_iter().map(|a| get_arm(cx, a)).collect(),
     ),
-    Expr::Block(blk) => Expr::Block(get_block(*blk).into()),
+    // `&&` and `||` only evaluate the rhs when they need to, like an if.
+    Expr::BinOp(lhs, BinOp::And, rhs) => {
+      let lhs = get_expr(cx, *lhs);
+      let rhs = get_expr(cx, *rhs);
+      if_match(
+        lhs,
+        expr_block(rhs),
+        expr_block(bool_ctor(&Ident::new("false"))),
+      )
+    }
+    Expr::BinOp(lhs, BinOp::Or, rhs) => {
+      let lhs = get_expr(cx, *lhs);
+      let rhs = get_expr(cx, *rhs);
+      if_match(
+        lhs,
+        expr_block(bool_ctor(&Ident::new("true"))),
+        expr_block(rhs),
+      )
+    }
+    Expr::BinOp(lhs, op, rhs) => {
+      let (name, negate) = bin_op_fn(op);
+      let args = vec![get_expr(cx, *lhs), get_expr(cx, *rhs)];
+      let call = Expr::FnCall(Ident::new(name), vec![], args);
+      if negate {
+        Expr::FnCall(Ident::new(birb_std_lib::NOT), vec![], vec![call])
+      } else {
+        call
+      }
+    }
+    Expr::Not(e) => Expr::FnCall(
+      Ident::new(birb_std_lib::NOT),
+      vec![],
+      vec![get_expr(cx, *e)],
+    ),
+    Expr::If(cond, yes, no) => {
+      let cond = get_expr(cx, *cond);
+      let yes = get_block(cx, *yes);
+      let no = get_block(cx, *no);
+      if_match(cond, yes, no)
+    }
+    Expr::Block(blk) => Expr::Block(get_block(cx, *blk).into()),
+    Expr::Return(e) => Expr::Return(get_expr(cx, *e).into()),
+  }
+}
+
+/// Returns the fn for the op, and whether to negate its result. The comparisons are all in terms of
+/// the fns of `Eq` and `Ord`, so they work for any type implementing those traits.
+fn bin_op_fn(op: BinOp) -> (&'static str, bool) {
+  match op {
+    BinOp::Add => (birb_std_lib::ADD, false),
+    BinOp::Sub => (birb_std_lib::SUB, false),
+    BinOp::Mul => (birb_std_lib::MUL, false),
+    BinOp::Div => (birb_std_lib::DIV, false),
+    BinOp::Eq => (birb_std_lib::EQ, false),
+    BinOp::Neq => (birb_std_lib::EQ, true),
+    BinOp::Lt => (birb_std_lib::LT, false),
+    BinOp::Gt => (birb_std_lib::GT, false),
+    BinOp::Le => (birb_std_lib::GT, true),
+    BinOp::Ge => (birb_std_lib::LT, true),
+    BinOp::And | BinOp::Or => unreachable!("desugared like an if"),
   }
 }
 
-fn get_block(block: Block) -> Block {
+/// Returns the match on the desugared bool which an if becomes.
+fn if_match(cond: Expr, yes: Block, no: Block) -> Expr {
+  let arm = |b, block| Arm {
+    pat: Pat::Ctor(bool_name(&Ident::new(b)), vec![Pat::Tuple(vec![])]),
+    guard: None,
+    block,
+  };
+  Expr::Match(cond.into(), vec![arm("true", yes), arm("false", no)])
+}
+
+fn expr_block(expr: Expr) -> Block {
   Block {
-    stmts: block.stmts.into_iter().map(get_stmt).collect(),
-    expr: block.expr.map(get_expr),
+    stmts: vec![],
+    expr: Some(expr),
   }
 }
 
-fn get_field(field: Field<Expr>) -> Field<Expr> {
+/// Desugars the pattern. The names in a let are always vars, since its pattern must match every
+/// value.
+fn get_pat(cx: &Cx, pat: Pat, let_: bool) -> Pat {
+  match pat {
+    Pat::Ident(id) if is_bool_lit(&id) => Pat::Ctor(bool_name(&id), vec![Pat::Tuple(vec![])]),
+    Pat::Ident(id) if !let_ && (cx.units.contains(&id) || is_nil(&id)) => Pat::Ctor(id, vec![]),
+    Pat::Wildcard | Pat::String_(..) | Pat::Number(..) | Pat::Ident(..) => pat,
+    Pat::Tuple(pats) => Pat::Tuple(get_pats(cx, pats, let_)),
+    Pat::List(pats, rest) => Pat::List(
+      get_pats(cx, pats, let_),
+      rest.map(|p| get_pat(cx, *p, let_).into()),
+    ),
+    Pat::Ctor(name, pats) => Pat::Ctor(name, get_pats(cx, pats, let_)),
+    Pat::Struct(name, fields, rest) => {
+      let fields = fields.into_iter().map(|f| match f {
+        Field::Ident(id) => Field::IdentAnd(id.clone(), get_pat(cx, Pat::Ident(id), let_)),
+        Field::IdentAnd(id, p) => Field::IdentAnd(id, get_pat(cx, p, let_)),
+      });
+      Pat::Struct(name, fields.collect(), rest)
+    }
+    Pat::Or(pats) => Pat::Or(get_pats(cx, pats, let_)),
+    Pat::At(name, pat) => Pat::At(name, get_pat(cx, *pat, let_).into()),
+  }
+}
+
+fn get_pats(cx: &Cx, pats: Vec<Pat>, let_: bool) -> Vec<Pat> {
+  pats.into_iter().map(|p| get_pat(cx, p, let_)).collect()
+}
+
+/// Adds the vars bound by the desugared pattern to the scope.
+fn bind_pat(cx: &mut Cx, pat: &Pat) {
+  match pat {
+    Pat::Wildcard | Pat::String_(..) | Pat::Number(..) => {}
+    Pat::Ident(id) => cx.vars.push(id.clone()),
+    Pat::Tuple(pats) | Pat::Ctor(_, pats) | Pat::Or(pats) => {
+      for p in pats {
+        bind_pat(cx, p);
+      }
+    }
+    Pat::List(pats, rest) => {
+      for p in pats.iter().chain(rest.as_deref()) {
+        bind_pat(cx, p);
+      }
+    }
+    Pat::Struct(_, fields, _) => {
+      for f in fields {
+        match f {
+          Field::Ident(id) => cx.vars.push(id.clone()),
+          Field::IdentAnd(_, p) => bind_pat(cx, p),
+        }
+      }
+    }
+    Pat::At(name, pat) => {
+      cx.vars.push(name.clone());
+      bind_pat(cx, pat);
+    }
+  }
+}
+
+/// `nil` in a pattern is the empty list, like a unit constructor.
+fn is_nil(id: &Ident) -> bool {
+  *id == Ident::new(birb_std_lib::NIL)
+}
+
+fn is_bool_lit(id: &Ident) -> bool {
+  *id == Ident::new("true") || *id == Ident::new("false")
+}
+
+fn bool_name(id: &Ident) -> Ident {
+  Ident::qualified(&Ident::new(birb_std_lib::BOOL), id)
+}
+
+fn bool_ctor(id: &Ident) -> Expr {
+  Expr::FnCall(bool_name(id), vec![], vec![Expr::Tuple(vec![])])
+}
+
+fn get_block(cx: &mut Cx, block: Block) -> Block {
+  let len = cx.vars.len();
+  let stmts = block.stmts.into_iter().map(|s| get_stmt(cx, s)).collect();
+  let expr = block.expr.map(|e| get_expr(cx, e));
+  cx.vars.truncate(len);
+  Block { stmts, expr }
+}
+
+fn get_field(cx: &mut Cx, field: Field<Expr>) -> Field<Expr> {
   match field {
     Field::Ident(..) => field,
-    Field::IdentAnd(id, expr) => Field::IdentAnd(id, get_expr(expr)),
+    Field::IdentAnd(id, expr) => Field::IdentAnd(id, get_expr(cx, expr)),
   }
 }
 
-fn get_arm(arm: Arm) -> Arm {
-  Arm {
-    pat: arm.pat,
-    block: get_block(arm.block),
-  }
+fn get_arm(cx: &mut Cx, arm: Arm) -> Arm {
+  let len = cx.vars.len();
+  let pat = get_pat(cx, arm.pat, false);
+  bind_pat(cx, &pat);
+  let ret = Arm {
+    pat,
+    guard: arm.guard.map(|e| get_expr(cx, e)),
+    block: get_block(cx, arm.block),
+  };
+  cx.vars.truncate(len);
+  ret
 }
 
-fn get_stmt(stmt: Stmt) -> Stmt {
+/// Desugars the statement, adding the vars it binds to the scope.
+fn get_stmt(cx: &mut Cx, stmt: Stmt) -> Stmt {
   match stmt {
-    Stmt::Let(pat, typ, expr) => Stmt::Let(pat, typ, get_expr(expr)),
+    Stmt::Let(pat, typ, expr) => {
+      let expr = get_expr(cx, expr);
+      let pat = get_pat(cx, pat, true);
+      bind_pat(cx, &pat);
+      Stmt::Let(pat, typ, expr)
+    }
   }
 }
@@ -3,11 +3,17 @@
 use crate::cst::{Kind, Kinded};
 use crate::ident::Ident;
 use crate::parse::Found;
+use crate::util::SliceDisplay;
+use crate::warning::Warning;
 use std::fmt;
 
 /// An error.
 #[derive(Debug)]
 pub enum Error {
+  /// A warning of a lint which was denied.
+  DeniedLint(Warning),
+  /// Error reading a file.
+  Io(std::path::PathBuf, std::io::Error),
   /// Invalid byte.
   InvalidByte(u8),
   /// Unclosed string literal.
@@ -18,32 +24,62 @@ pub enum Error {
   InvalidNumber(std::num::ParseIntError),
   /// Parse error, where we expected one thing but found another thing.
   Parse(&'static str, Found),
+  /// A module which imports itself, possibly through other modules. The first and last modules in
+  /// the cycle are the same.
+  CyclicImport(Vec<Ident>),
+  /// An import of a package which the importing package does not depend on directly.
+  NotDependency(Ident),
   /// Empty kinded params, like `struct Foo[] { x: Nat }`.
   EmptyKindedParams,
   /// Empty kinded arguments, like `Foo[] { x: 3 }`.
   EmptyKindedArgs,
   /// Undefined identifier.
   UndefinedIdentifier(Ident),
+  /// Use of a definition which is not `pub` outside of its module.
+  Private(Ident),
   /// Kind mismatch, where we expected something to have the left Kind but it had the right Kind
   /// instead.
   MismatchedKinds(Kind, Kind),
+  /// Like MismatchedKinds, but for a param whose kind was inferred, with the earlier use of the
+  /// param and then the use which conflicts with it.
+  MismatchedParamKinds(Ident, Box<(Kinded, Kinded)>, Box<(Kind, Kind)>),
   /// Incorrect number of arguments.
   WrongNumArgs(Ident, usize, usize),
+  /// A big arg of a call to the fn could not be inferred, and so must be written.
+  CannotInferBigArg(Ident, Ident),
   /// Application of a Kinded where the Kinded did not have Arrow kind.
   InvalidKindedApp(Ident, Kind),
   /// Duplicated field in a struct.
   DuplicateField(Ident, Ident),
   /// Duplicated identifier.
   DuplicateIdentifier(Ident),
+  /// An unqualified constructor name which is in more than one enum. Also has the qualified names.
+  AmbiguousCtor(Ident, Vec<Ident>),
   /// Undefined field in an struct expression or field get.
   NoSuchField(Ident, Ident),
+  /// A field missing from a struct expression or pattern.
+  MissingField(Ident, Ident),
   /// Type mismatch, where we expected something to have the left Kinded but it had the right Kinded
   /// instead.
   MismatchedTypes(Kinded, Kinded),
+  /// Like MismatchedTypes, but at least one of the types uses an alias. Each type is given as a pair
+  /// of the type as written and then expanded.
+  MismatchedAliasedTypes(Box<(Kinded, Kinded)>, Box<(Kinded, Kinded)>),
+  /// Type aliases which refer to themselves, through the others in the cycle.
+  CyclicAlias(Vec<Ident>),
   /// Field get on something not of struct type.
   NotStruct(Ident),
+  /// A ctor with named fields used like one with positional payloads, like `circle(3)`.
+  NamedCtor(Ident),
+  /// A struct expression or pattern with a name which is neither a struct nor a ctor with named
+  /// fields, like `some { x: 3 }`. Also struct update syntax for a ctor with named fields.
+  NotStructName(Ident),
   /// A pattern didn't make sense for this match.
   InvalidPattern(Kinded),
+  /// A variable bound in some but not all alternatives of an or-pattern.
+  InconsistentOrPat(Ident),
+  /// A definition without a body which is not an intrinsic.
+  MissingBody(Ident),
   /// No main function.
   NoMain,
   /// Invalid use of an effect in a function.
@@ -52,10 +88,28 @@ pub enum Error {
   NoExprForBlock,
   /// An empty match expression.
   EmptyMatch,
+  /// An empty list literal, whose element type is unknown.
+  EmptyList,
+  /// A return outside of a function body, like in a contract.
+  InvalidReturn,
   /// Invalid main function.
   InvalidMain,
   /// Non-exhaustive match.
   NonExhaustiveMatch,
+  /// A let whose pattern did not match the value, like `let [a] = [1, 2];`.
+  NonExhaustiveLet,
+  /// A type which cannot be compared was used as a Map or Set key.
+  NotComparable(Kinded),
+  /// A trait fn which is not like `fn name(x: Self, ...): T;`.
+  InvalidTraitFn(Ident),
+  /// An impl for a type which is not a named type applied to the big params of the impl.
+  InvalidImpl(Kinded),
+  /// More than one impl of a trait for a type.
+  DuplicateImpl(Ident, Kinded),
+  /// An impl missing a fn of the trait.
+  MissingMethod(Ident, Ident),
+  /// Use of a trait for a type which does not implement it.
+  NoImpl(Ident, Kinded),
   /// Requires contract failed.
   RequiresFailed(Ident),
   /// Ensures contract failed.
@@ -67,24 +121,40 @@ impl fmt::Display for Error {
     match self {
       Self::InvalidByte(b) => write!(f, "invalid byte: {}", (*b as char)),
       Self::UnclosedString => write!(f, "unclosed string literal"),
+      Self::Io(path, e) => write!(f, "{}: {}", path.display(), e),
       Self::InvalidUTF8(e) => write!(f, "invalid utf-8: {}", e),
       Self::InvalidNumber(e) => write!(f, "invalid number: {}", e),
       Self::Parse(expected, found) => {
         write!(f, "parse error: expected {}, found {}", expected, found)
       }
+      Self::CyclicImport(modules) => write!(
+        f,
+        "import cycle: {}",
+        SliceDisplay::new("", modules, "").sep(" -> ")
+      ),
+      Self::NotDependency(name) => write!(f, "{} is not a dependency of this package", name),
       Self::EmptyKindedParams => write!(f, "empty type/effect params"),
       Self::EmptyKindedArgs => write!(f, "empty type/effect args"),
       Self::UndefinedIdentifier(id) => write!(f, "undefined identifier: {}", id),
+      Self::Private(id) => write!(f, "{} is private to its module", id),
       Self::MismatchedKinds(expected, found) => write!(
         f,
         "mismatched kinds: expected {}, found {}",
         expected, found
       ),
+      Self::MismatchedParamKinds(id, uses, kinds) => write!(
+        f,
+        "mismatched kinds for {} in {} and in {}: expected {}, found {}",
+        id, uses.0, uses.1, kinds.0, kinds.1
+      ),
       Self::WrongNumArgs(id, expected, found) => write!(
         f,
         "wrong number of arguments for {}: expected {}, found {}",
         id, expected, found
       ),
+      Self::CannotInferBigArg(id, param) => {
+        write!(f, "cannot infer {} for {}: write the big args", param, id)
+      }
       Self::InvalidKindedApp(bi, found) => write!(
         f,
         "invalid kind for {}: expected an arrow kind, found {}",
@@ -94,20 +164,65 @@ impl fmt::Display for Error {
         write!(f, "duplicate field for {}: {}", struct_, field)
       }
       Self::DuplicateIdentifier(id) => write!(f, "duplicate identifier: {}", id),
+      Self::AmbiguousCtor(id, qualified) => write!(
+        f,
+        "ambiguous constructor {}: could be {}",
+        id,
+        SliceDisplay::new("", qualified, "")
+      ),
       Self::NoSuchField(struct_, field) => write!(f, "no such field for {}: {}", struct_, field),
+      Self::MissingField(struct_, field) => write!(f, "missing field for {}: {}", struct_, field),
       Self::MismatchedTypes(expected, found) => write!(
         f,
         "mismatched types: expected {}, found {}",
         expected, found
       ),
+      Self::MismatchedAliasedTypes(expected, found) => {
+        let (expected, expected_exp) = &**expected;
+        let (found, found_exp) = &**found;
+        write!(f, "mismatched types: expected {}", expected)?;
+        if expected != expected_exp {
+          write!(f, " (alias for {})", expected_exp)?;
+        }
+        write!(f, ", found {}", found)?;
+        if found != found_exp {
+          write!(f, " (alias for {})", found_exp)?;
+        }
+        Ok(())
+      }
+      Self::CyclicAlias(names) => write!(
+        f,
+        "type alias cycle: {}",
+        SliceDisplay::new("", names, "").sep(" -> ")
+      ),
       Self::NotStruct(field) => write!(f, "cannot get field {} of non-struct type", field),
+      Self::NamedCtor(id) => write!(f, "constructor {} has named fields", id),
+      Self::NotStructName(id) => write!(f, "{} is not a struct", id),
       Self::InvalidPattern(typ) => write!(f, "invalid pattern for type {}", typ),
+      Self::InconsistentOrPat(id) => {
+        write!(
+          f,
+          "{} is not bound in every alternative of the or-pattern",
+          id
+        )
+      }
+      Self::MissingBody(id) => write!(f, "missing body for {}", id),
       Self::NoMain => write!(f, "no main function"),
       Self::InvalidEffectUse(fn_, ef) => write!(f, "invalid use of effect {} in {}", ef, fn_),
       Self::NoExprForBlock => write!(f, "no expression at the end of the block"),
       Self::EmptyMatch => write!(f, "empty match expression"),
+      Self::EmptyList => write!(f, "empty list literal (use nil instead)"),
+      Self::InvalidReturn => write!(f, "return outside of a function body"),
       Self::InvalidMain => write!(f, "invalid main"),
       Self::NonExhaustiveMatch => write!(f, "non-exhaustive match"),
+      Self::NonExhaustiveLet => write!(f, "non-exhaustive let"),
+      Self::NotComparable(typ) => write!(f, "cannot compare values of type {}", typ),
+      Self::InvalidTraitFn(id) => write!(f, "invalid trait fn: {}", id),
+      Self::InvalidImpl(typ) => write!(f, "invalid type for impl: {}", typ),
+      Self::DuplicateImpl(trait_, typ) => write!(f, "duplicate impl of {} for {}", trait_, typ),
+      Self::MissingMethod(trait_, id) => write!(f, "missing fn {} of {} in impl", id, trait_),
+      Self::NoImpl(trait_, typ) => write!(f, "no impl of {} for {}", trait_, typ),
+      Self::DeniedLint(w) => write!(f, "{} (the {} lint is denied)", w, w.lint()),
       Self::RequiresFailed(func) => write!(f, "requires failed for {}", func),
       Self::EnsuresFailed(func) => write!(f, "ensures failed for {}", func),
     }
@@ -117,29 +232,53 @@ impl fmt::Display for Error {
 impl std::error::Error for Error {
   fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
     match self {
+      Self::Io(_, e) => Some(e),
       Self::InvalidUTF8(e) => Some(e),
       Self::InvalidNumber(e) => Some(e),
       Self::InvalidByte(..)
       | Self::UnclosedString
       | Self::Parse(..)
+      | Self::CyclicImport(..)
+      | Self::NotDependency(..)
       | Self::EmptyKindedParams
       | Self::EmptyKindedArgs
       | Self::UndefinedIdentifier(..)
+      | Self::Private(..)
       | Self::MismatchedKinds(..)
+      | Self::MismatchedParamKinds(..)
       | Self::WrongNumArgs(..)
+      | Self::CannotInferBigArg(..)
       | Self::InvalidKindedApp(..)
       | Self::DuplicateField(..)
       | Self::DuplicateIdentifier(..)
+      | Self::AmbiguousCtor(..)
       | Self::NoSuchField(..)
+      | Self::MissingField(..)
       | Self::MismatchedTypes(..)
+      | Self::MismatchedAliasedTypes(..)
+      | Self::CyclicAlias(..)
       | Self::NotStruct(..)
+      | Self::NamedCtor(..)
+      | Self::NotStructName(..)
       | Self::InvalidPattern(..)
+      | Self::InconsistentOrPat(..)
+      | Self::MissingBody(..)
       | Self::NoMain
       | Self::InvalidEffectUse(..)
       | Self::NoExprForBlock
       | Self::EmptyMatch
+      | Self::EmptyList
+      | Self::InvalidReturn
       | Self::InvalidMain
       | Self::NonExhaustiveMatch
+      | Self::NonExhaustiveLet
+      | Self::NotComparable(..)
+      | Self::InvalidTraitFn(..)
+      | Self::InvalidImpl(..)
+      | Self::DuplicateImpl(..)
+      | Self::MissingMethod(..)
+      | Self::NoImpl(..)
+      | Self::DeniedLint(..)
       | Self::RequiresFailed(..)
       | Self::EnsuresFailed(..) => None,
     }
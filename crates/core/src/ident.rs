@@ -3,7 +3,7 @@
 use std::fmt;
 
 /// An identifier.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Ident(String);
 
 impl Ident {
@@ -11,6 +11,39 @@ impl Ident {
   pub fn new(s: &str) -> Self {
     Self(s.to_owned())
   }
+
+  /// Construct a new qualified Ident, like `Option::none`.
+  pub fn qualified(qual: &Ident, name: &Ident) -> Self {
+    Self(format!("{}::{}", qual.0, name.0))
+  }
+
+  /// Construct the name of a fn in an impl of a trait for a type, like `<Nat as Eq>::eq`. Such
+  /// names can't be written in the source.
+  pub fn impl_fn(trait_: &Ident, type_: &Ident, name: &Ident) -> Self {
+    Self(format!("<{} as {}>::{}", type_.0, trait_.0, name.0))
+  }
+
+  /// Split a qualified Ident into the qualifier and the unqualified name. Returns None if this is
+  /// not qualified.
+  pub fn split(&self) -> Option<(Ident, Ident)> {
+    let (qual, name) = self.0.rsplit_once("::")?;
+    Some((Self::new(qual), Self::new(name)))
+  }
+
+  /// Split a qualified Ident into the first part and the rest. Returns None if this is not
+  /// qualified.
+  pub fn split_first(&self) -> Option<(Ident, Ident)> {
+    let (first, rest) = self.0.split_once("::")?;
+    Some((Self::new(first), Self::new(rest)))
+  }
+
+  /// Returns this Ident with any qualifier removed.
+  pub fn unqualified(&self) -> Ident {
+    match self.split() {
+      None => self.clone(),
+      Some((_, name)) => name,
+    }
+  }
 }
 
 impl fmt::Display for Ident {
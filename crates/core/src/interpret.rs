@@ -1,36 +1,94 @@
 //! Interpretation.
 
-use crate::cst::{Block, Expr, Field, Pat, Stmt, TopDefn};
+use crate::cst::{Block, Expr, Field, Kinded, Pat, Payload, Stmt, TopDefn};
 use crate::error::{Error, Result};
 use crate::ident::Ident;
+use crate::persistent::{List, Map, Set};
 use crate::std_lib as birb_std_lib;
 use crate::util::SliceDisplay;
 use std::collections::HashMap;
 use std::fmt;
 
-/// Steps the expression `main()` in the given context to a value. Requires that the context be
+/// Steps the expression `main(args)` in the given context to a value. Requires that the context be
 /// statically checked and have a main function.
-pub fn get(cx: HashMap<Ident, TopDefn>) -> Result<Value> {
-  let main = &cx[&Ident::new("main")];
-  let main = match main {
+pub fn get(cx: HashMap<Ident, TopDefn>, args: &[String]) -> Result<Value> {
+  let main_name = Ident::new("main");
+  let main = match &cx[&main_name] {
     TopDefn::Fn_(x) => x,
-    TopDefn::Struct(..) | TopDefn::Enum(..) => unreachable!(),
+    TopDefn::Struct(..)
+    | TopDefn::Enum(..)
+    | TopDefn::Type(..)
+    | TopDefn::Trait(..)
+    | TopDefn::Impl(..)
+    | TopDefn::Import(..) => unreachable!(),
   };
-  block_eval(&main.body, HashMap::new(), &cx)
+  let args = args.iter().map(|a| Value::String_(a.clone()));
+  let m: HashMap<_, _> = match &main.params[..] {
+    [p] if matches!(&p.type_, Kinded::Ident(name, _) if *name == Ident::new(birb_std_lib::LIST)) => {
+      std::iter::once((p.ident.clone(), Value::List(args.collect()))).collect()
+    }
+    ps => {
+      if ps.len() != args.len() {
+        return Err(Error::WrongNumArgs(main_name, ps.len(), args.len()));
+      }
+      ps.iter().map(|p| p.ident.clone()).zip(args).collect()
+    }
+  };
+  match block_eval(main.body.as_ref().unwrap(), m, &cx) {
+    Ok(v) | Err(Unwind::Return(v)) => Ok(v),
+    Err(Unwind::Error(e)) => Err(e),
+  }
+}
+
+/// The reason evaluation of an expression stopped before producing a value.
+enum Unwind {
+  /// A runtime error.
+  Error(Error),
+  /// A `return` from the enclosing function, with the returned value.
+  Return(Value),
+}
+
+impl From<Error> for Unwind {
+  fn from(e: Error) -> Self {
+    Self::Error(e)
+  }
+}
+
+type EvalResult<T> = std::result::Result<T, Unwind>;
+
+/// Returns the process exit code for the value returned by `main`. Only `failure(n)` from the
+/// `Exit` enum gives a non-zero exit code, which is `n` clamped to 1 to 255 so that it still means
+/// failure.
+pub fn exit_code(val: &Value) -> i32 {
+  let failure = Ident::qualified(
+    &Ident::new(birb_std_lib::EXIT),
+    &Ident::new(birb_std_lib::FAILURE),
+  );
+  match val {
+    Value::Ctor(name, vs) if *name == failure => match vs[..] {
+      [Value::Number(n)] => n.clamp(1, 255) as i32,
+      _ => unreachable!("type error: {} holds a Nat", failure),
+    },
+    _ => 0,
+  }
 }
 
 fn block_eval(
   blk: &Block,
   mut m: HashMap<Ident, Value>,
   cx: &HashMap<Ident, TopDefn>,
-) -> Result<Value> {
+) -> EvalResult<Value> {
   for s in blk.stmts.iter() {
     let (pat, expr) = match s {
       Stmt::Let(p, _, e) => (p, e),
     };
     let val = expr_eval(expr, &m, cx)?;
-    let mm = pat_match(pat, &val);
-    let mm = mm.unwrap();
+    // statics doesn't check that the pattern matches every value.
+    let mm = match pat_match(pat, &val) {
+      Some(mm) => mm,
+      None => return Err(Error::NonExhaustiveLet.into()),
+    };
+    // the vars shadow earlier ones with the same names, as in statics.
     m.extend(mm);
   }
   expr_eval(blk.expr.as_ref().unwrap(), &m, cx)
@@ -63,18 +121,81 @@ fn pat_match(pat: &Pat, val: &Value) -> Option<HashMap<Ident, Value>> {
       }
       Some(m)
     }
-    (Pat::Ctor(name_lt, pat_lt), Value::Ctor(name_rt, pat_rt)) => {
-      if name_lt == name_rt {
-        pat_match(&*pat_lt, &*pat_rt)
-      } else {
-        None
+    (Pat::List(xs, rest), Value::List(ys)) => {
+      let enough = match rest {
+        None => xs.len() == ys.len(),
+        Some(_) => xs.len() <= ys.len(),
+      };
+      if !enough {
+        return None;
       }
+      let mut m = HashMap::new();
+      for (x, y) in xs.iter().zip(ys.iter()) {
+        match pat_match(x, y) {
+          None => return None,
+          Some(x) => m.extend(x),
+        }
+      }
+      if let Some(rest) = rest {
+        // the rest shares the cells of the list.
+        match pat_match(rest, &Value::List(ys.skip(xs.len()))) {
+          None => return None,
+          Some(x) => m.extend(x),
+        }
+      }
+      Some(m)
+    }
+    // the cons and nil patterns, which are checked by the statics to be for lists.
+    (Pat::Ctor(name, pats), Value::List(ys)) => match (&pats[..], ys.split_first()) {
+      ([], None) => Some(HashMap::new()),
+      ([x, rest], Some((y, ys))) => {
+        assert_eq!(*name, Ident::new(birb_std_lib::CONS));
+        let mut m = pat_match(x, y)?;
+        m.extend(pat_match(rest, &Value::List(ys))?);
+        Some(m)
+      }
+      _ => None,
+    },
+    (Pat::Ctor(name_lt, pats), Value::Ctor(name_rt, vs)) => {
+      if name_lt.unqualified() != name_rt.unqualified() {
+        return None;
+      }
+      assert_eq!(pats.len(), vs.len());
+      let mut m = HashMap::new();
+      for (p, v) in pats.iter().zip(vs) {
+        m.extend(pat_match(p, v)?);
+      }
+      Some(m)
     }
     (Pat::Ident(name), _) => {
       let mut m = HashMap::new();
       m.insert(name.clone(), val.clone());
       Some(m)
     }
+    (Pat::Struct(name_lt, fields, _), Value::Struct(name_rt, vs)) => {
+      if name_lt.unqualified() != name_rt.unqualified() {
+        return None;
+      }
+      let mut m = HashMap::new();
+      for f in fields {
+        let (x, pat) = match f {
+          Field::IdentAnd(x, pat) => (x, pat),
+          Field::Ident(..) => unreachable!(),
+        };
+        let v = vs.iter().find_map(|v| match v {
+          Field::IdentAnd(y, v) if x == y => Some(v),
+          _ => None,
+        });
+        m.extend(pat_match(pat, v.unwrap())?);
+      }
+      Some(m)
+    }
+    (Pat::Or(pats), _) => pats.iter().find_map(|p| pat_match(p, val)),
+    (Pat::At(name, pat), _) => {
+      let mut m = pat_match(pat, val)?;
+      m.insert(name.clone(), val.clone());
+      Some(m)
+    }
     _ => None,
   }
 }
@@ -83,7 +204,7 @@ fn expr_eval(
   expr: &Expr,
   m: &HashMap<Ident, Value>,
   cx: &HashMap<Ident, TopDefn>,
-) -> Result<Value> {
+) -> EvalResult<Value> {
   let ret = match expr {
     Expr::String_(x) => Value::String_(x.clone()),
     Expr::Number(x) => Value::Number(*x),
@@ -94,7 +215,14 @@ fn expr_eval(
       }
       Value::Tuple(t)
     }
-    Expr::Struct(name, _, fs) => {
+    Expr::List(xs) => {
+      let mut t = Vec::with_capacity(xs.len());
+      for x in xs {
+        t.push(expr_eval(x, m, cx)?);
+      }
+      Value::List(t.into_iter().collect())
+    }
+    Expr::Struct(name, _, fs, base) => {
       let mut vs = Vec::with_capacity(fs.len());
       for field in fs {
         match field {
@@ -105,7 +233,51 @@ fn expr_eval(
           Field::IdentAnd(i, j) => vs.push(Field::IdentAnd(i.clone(), expr_eval(j, m, cx)?)),
         };
       }
-      Value::Struct(name.clone(), vs)
+      if let Some(base) = base {
+        // keep the order of the fields from the base, replacing the ones that were given.
+        let base_vs = match expr_eval(base, m, cx)? {
+          Value::Struct(_, base_vs) => base_vs,
+          _ => unreachable!(),
+        };
+        let mut given: HashMap<_, _> = vs
+          .into_iter()
+          .map(|f| match f {
+            Field::IdentAnd(i, v) => (i, v),
+            Field::Ident(..) => unreachable!(),
+          })
+          .collect();
+        vs = base_vs
+          .into_iter()
+          .map(|f| match f {
+            Field::IdentAnd(i, v) => {
+              let v = given.remove(&i).unwrap_or(v);
+              Field::IdentAnd(i, v)
+            }
+            Field::Ident(..) => unreachable!(),
+          })
+          .collect();
+      }
+      let (name, defn_fields) = match cx.get(name) {
+        Some(TopDefn::Struct(s)) => (name.clone(), &s.fields),
+        _ => {
+          let name = ctor_name(cx, name);
+          let (enum_name, ctor) = name.split().unwrap();
+          let fields = match &cx[&enum_name] {
+            TopDefn::Enum(e) => e.ctors.iter().find_map(|p| match &p.type_ {
+              Payload::Named(fields) if p.ident == ctor => Some(fields),
+              _ => None,
+            }),
+            _ => None,
+          };
+          (name, fields.unwrap())
+        }
+      };
+      // order the fields as in the definition, so values which are equal have equal fields.
+      vs.sort_by_key(|f| match f {
+        Field::IdentAnd(i, _) => defn_fields.iter().position(|p| p.ident == *i),
+        Field::Ident(..) => unreachable!(),
+      });
+      Value::Struct(name, vs)
     }
     Expr::Ident(name) => m[name].clone(),
     Expr::FnCall(name, _, xs) => {
@@ -113,62 +285,60 @@ fn expr_eval(
       for x in xs {
         vs.push(expr_eval(x, m, cx)?);
       }
-      if *name == Ident::new(birb_std_lib::ADD) {
-        return Ok(nat_math_op(vs, |x, y| x + y));
-      }
-      if *name == Ident::new(birb_std_lib::SUB) {
-        return Ok(nat_math_op(vs, |x, y| x - y));
-      }
-      if *name == Ident::new(birb_std_lib::MUL) {
-        return Ok(nat_math_op(vs, |x, y| x * y));
-      }
-      if *name == Ident::new(birb_std_lib::DIV) {
-        return Ok(nat_math_op(vs, |x, y| x / y));
-      }
-      if *name == Ident::new(birb_std_lib::EQ) {
-        return Ok(nat_cmp_op(vs, |x, y| x == y));
-      }
-      if *name == Ident::new(birb_std_lib::LT) {
-        return Ok(nat_cmp_op(vs, |x, y| x < y));
-      }
-      if *name == Ident::new(birb_std_lib::GT) {
-        return Ok(nat_cmp_op(vs, |x, y| x > y));
-      }
-      if *name == Ident::new(birb_std_lib::AND) {
-        return Ok(bool_op(vs, |x, y| x && y));
-      }
-      if *name == Ident::new(birb_std_lib::OR) {
-        return Ok(bool_op(vs, |x, y| x || y));
-      }
-      match cx.get(name) {
-        Some(TopDefn::Fn_(f)) => {
-          let mut m = m.clone();
-          for (p, v) in f.params.iter().zip(vs) {
-            m.insert(p.ident.clone(), v);
-          }
-          if let Some(req) = &f.requires {
-            let e = expr_eval(req, &m, cx)?;
-            if !get_bool(e) {
-              return Err(Error::RequiresFailed(name.clone()));
-            }
-          }
-          let ret = block_eval(&f.body, m.clone(), cx)?;
-          if let Some(ens) = &f.ensures {
-            m.insert(Ident::new("ret"), ret.clone());
-            let e = expr_eval(ens, &m, cx)?;
-            if !get_bool(e) {
-              return Err(Error::EnsuresFailed(name.clone()));
+      let f = match cx.get(name) {
+        Some(TopDefn::Fn_(f)) => f,
+        Some(TopDefn::Struct(..))
+        | Some(TopDefn::Enum(..))
+        | Some(TopDefn::Type(..))
+        | Some(TopDefn::Trait(..))
+        | Some(TopDefn::Impl(..))
+        | Some(TopDefn::Import(..)) => unreachable!(),
+        None => match get_trait(cx, name) {
+          // dispatch on the type of the first arg.
+          Some(trait_) => {
+            let impl_fn = type_name(cx, &vs[0])
+              .and_then(|t| cx.get(&Ident::impl_fn(trait_, &t, &name.unqualified())));
+            match impl_fn {
+              Some(TopDefn::Fn_(f)) => f,
+              // statics checked the values can be compared structurally.
+              None if *trait_ == Ident::new(birb_std_lib::EQ_TRAIT) => {
+                return Ok(intrinsic(&Ident::new(birb_std_lib::EQ_ANY), vs))
+              }
+              _ => unreachable!(),
             }
           }
-          ret
+          None => return Ok(Value::Ctor(ctor_name(cx, name), vs)),
+        },
+      };
+      // the body sees only the params, not the vars of the caller.
+      let mut m: HashMap<_, _> = f
+        .params
+        .iter()
+        .zip(vs.iter())
+        .map(|(p, v)| (p.ident.clone(), v.clone()))
+        .collect();
+      if let Some(req) = &f.requires {
+        let e = expr_eval(req, &m, cx)?;
+        if !get_bool(e) {
+          return Err(Error::RequiresFailed(name.clone()).into());
         }
-        Some(TopDefn::Struct(..)) | Some(TopDefn::Enum(..)) => unreachable!(),
-        None => {
-          let v = vs.pop().unwrap();
-          assert!(vs.is_empty());
-          Value::Ctor(name.clone(), v.into())
+      }
+      let ret = match &f.body {
+        None => intrinsic(name, vs),
+        // a return inside the body stops here, so the ensures is still checked.
+        Some(body) => match block_eval(body, m.clone(), cx) {
+          Ok(v) | Err(Unwind::Return(v)) => v,
+          Err(e @ Unwind::Error(_)) => return Err(e),
+        },
+      };
+      if let Some(ens) = &f.ensures {
+        m.insert(Ident::new("ret"), ret.clone());
+        let e = expr_eval(ens, &m, cx)?;
+        if !get_bool(e) {
+          return Err(Error::EnsuresFailed(name.clone()).into());
         }
       }
+      ret
     }
     Expr::FieldGet(inner, name) => {
       let val = expr_eval(inner, m, cx)?;
@@ -190,6 +360,9 @@ fn expr_eval(
       }
     }
     Expr::MethodCall(..) => unreachable!("eval method call"),
+    Expr::BinOp(..) => unreachable!("eval bin op"),
+    Expr::Not(..) => unreachable!("eval not"),
+    Expr::If(..) => unreachable!("eval if"),
     Expr::Match(e, xs) => {
       let v = expr_eval(e, m, cx)?;
       for x in xs {
@@ -197,18 +370,218 @@ fn expr_eval(
           Some(map) => {
             let mut m = m.clone();
             m.extend(map);
+            if let Some(guard) = &x.guard {
+              if !get_bool(expr_eval(guard, &m, cx)?) {
+                continue;
+              }
+            }
             return block_eval(&x.block, m, cx);
           }
           None => continue,
         }
       }
-      return Err(Error::NonExhaustiveMatch);
+      return Err(Error::NonExhaustiveMatch.into());
     }
     Expr::Block(b) => block_eval(&*b, m.clone(), cx)?,
+    Expr::Return(e) => return Err(Unwind::Return(expr_eval(e, m, cx)?)),
   };
   Ok(ret)
 }
 
+/// Returns the name of the trait with this fn, if any.
+fn get_trait<'a>(cx: &'a HashMap<Ident, TopDefn>, name: &Ident) -> Option<&'a Ident> {
+  cx.values().find_map(|td| match td {
+    TopDefn::Trait(t) if t.fns.iter().any(|f| f.name == *name) => Some(&t.name),
+    _ => None,
+  })
+}
+
+/// Returns the name of the ctor qualified by its enum, like `Option::some`, so that values know
+/// their type.
+fn ctor_name(cx: &HashMap<Ident, TopDefn>, name: &Ident) -> Ident {
+  if let Some((qual, _)) = name.split() {
+    if let Some(TopDefn::Enum(..)) = cx.get(&qual) {
+      return name.clone();
+    }
+  }
+  let enum_name = cx.values().find_map(|td| match td {
+    TopDefn::Enum(e) if e.ctors.iter().any(|p| p.ident == *name) => Some(&e.name),
+    _ => None,
+  });
+  Ident::qualified(enum_name.unwrap(), name)
+}
+
+/// Returns the name of the type of the value, for finding impls of traits. Tuples have no name, so
+/// they have no impls.
+fn type_name(cx: &HashMap<Ident, TopDefn>, val: &Value) -> Option<Ident> {
+  let name = match val {
+    Value::String_(..) => birb_std_lib::STR,
+    Value::Number(..) => birb_std_lib::NAT,
+    Value::List(..) => birb_std_lib::LIST,
+    Value::Map(..) => birb_std_lib::MAP,
+    Value::Set(..) => birb_std_lib::SET,
+    Value::Struct(name, _) if matches!(cx.get(name), Some(TopDefn::Struct(..))) => {
+      return Some(name.clone())
+    }
+    Value::Struct(name, _) | Value::Ctor(name, _) => return name.split().map(|(t, _)| t),
+    Value::Tuple(..) => return None,
+  };
+  Some(Ident::new(name))
+}
+
+/// Evaluates a call to an intrinsic function from the prelude.
+fn intrinsic(name: &Ident, mut vs: Vec<Value>) -> Value {
+  if *name == Ident::new(birb_std_lib::ADD) {
+    return nat_math_op(vs, |x, y| x + y);
+  }
+  if *name == Ident::new(birb_std_lib::SUB) {
+    return nat_math_op(vs, |x, y| x - y);
+  }
+  if *name == Ident::new(birb_std_lib::MUL) {
+    return nat_math_op(vs, |x, y| x * y);
+  }
+  if *name == Ident::new(birb_std_lib::DIV) {
+    return nat_math_op(vs, |x, y| x / y);
+  }
+  if *name == Ident::new(birb_std_lib::EQ) {
+    return cmp_op(vs, |x, y| x == y);
+  }
+  if *name == Ident::new(birb_std_lib::EQ_ANY) {
+    return cmp_op(vs, |x, y| x == y);
+  }
+  if *name == Ident::new(birb_std_lib::LT) {
+    return cmp_op(vs, |x, y| x < y);
+  }
+  if *name == Ident::new(birb_std_lib::GT) {
+    return cmp_op(vs, |x, y| x > y);
+  }
+  if *name == Ident::new(birb_std_lib::SHOW) {
+    let s = match vs.pop().unwrap() {
+      Value::Number(n) => n.to_string(),
+      Value::String_(s) => s,
+      _ => unreachable!(),
+    };
+    assert!(vs.is_empty());
+    return Value::String_(s);
+  }
+  if *name == Ident::new(birb_std_lib::CONCAT) {
+    let y = get_string(vs.pop().unwrap());
+    let mut x = get_string(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    x.push_str(&y);
+    return Value::String_(x);
+  }
+  if *name == Ident::new(birb_std_lib::NIL) {
+    assert!(vs.is_empty());
+    return Value::List(List::new());
+  }
+  if *name == Ident::new(birb_std_lib::CONS) {
+    let xs = get_list(vs.pop().unwrap());
+    let x = vs.pop().unwrap();
+    assert!(vs.is_empty());
+    return Value::List(xs.cons(x));
+  }
+  if *name == Ident::new(birb_std_lib::LEN) {
+    let xs = get_list(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::Number(xs.len() as u64);
+  }
+  if *name == Ident::new(birb_std_lib::APPEND) {
+    let ys = get_list(vs.pop().unwrap());
+    let xs = get_list(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::List(xs.append(&ys));
+  }
+  if *name == Ident::new(birb_std_lib::REVERSE) {
+    let xs = get_list(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::List(xs.reverse());
+  }
+  if *name == Ident::new(birb_std_lib::NTH) {
+    let n = get_number(vs.pop().unwrap());
+    let xs = get_list(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    // the requires contract ensures this is in bounds.
+    return xs.nth(n as usize).unwrap().clone();
+  }
+  if *name == Ident::new(birb_std_lib::MAP_NEW) {
+    assert!(vs.is_empty());
+    return Value::Map(Map::new());
+  }
+  if *name == Ident::new(birb_std_lib::MAP_INSERT) {
+    let v = vs.pop().unwrap();
+    let k = vs.pop().unwrap();
+    let m = get_map(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::Map(m.insert(k, v));
+  }
+  if *name == Ident::new(birb_std_lib::MAP_REMOVE) {
+    let k = vs.pop().unwrap();
+    let m = get_map(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::Map(m.remove(&k));
+  }
+  if *name == Ident::new(birb_std_lib::MAP_CONTAINS) {
+    let k = vs.pop().unwrap();
+    let m = get_map(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return mk_bool(m.contains_key(&k));
+  }
+  if *name == Ident::new(birb_std_lib::MAP_GET) {
+    let k = vs.pop().unwrap();
+    let m = get_map(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    // the requires contract ensures this is present.
+    return m.get(&k).unwrap().clone();
+  }
+  if *name == Ident::new(birb_std_lib::MAP_SIZE) {
+    let m = get_map(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::Number(m.len() as u64);
+  }
+  if *name == Ident::new(birb_std_lib::MAP_ENTRIES) {
+    let m = get_map(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    let entries = m
+      .iter()
+      .map(|(k, v)| Value::Tuple(vec![k.clone(), v.clone()]));
+    return Value::List(entries.collect());
+  }
+  if *name == Ident::new(birb_std_lib::SET_NEW) {
+    assert!(vs.is_empty());
+    return Value::Set(Set::new());
+  }
+  if *name == Ident::new(birb_std_lib::SET_INSERT) {
+    let x = vs.pop().unwrap();
+    let s = get_set(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::Set(s.insert(x));
+  }
+  if *name == Ident::new(birb_std_lib::SET_REMOVE) {
+    let x = vs.pop().unwrap();
+    let s = get_set(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::Set(s.remove(&x));
+  }
+  if *name == Ident::new(birb_std_lib::SET_CONTAINS) {
+    let x = vs.pop().unwrap();
+    let s = get_set(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return mk_bool(s.contains(&x));
+  }
+  if *name == Ident::new(birb_std_lib::SET_SIZE) {
+    let s = get_set(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::Number(s.len() as u64);
+  }
+  if *name == Ident::new(birb_std_lib::SET_TO_LIST) {
+    let s = get_set(vs.pop().unwrap());
+    assert!(vs.is_empty());
+    return Value::List(s.iter().cloned().collect());
+  }
+  unreachable!("not an intrinsic: {}", name)
+}
+
 fn get_number(val: Value) -> u64 {
   match val {
     Value::Number(n) => n,
@@ -216,17 +589,49 @@ fn get_number(val: Value) -> u64 {
   }
 }
 
+fn get_string(val: Value) -> String {
+  match val {
+    Value::String_(s) => s,
+    _ => unreachable!(),
+  }
+}
+
+fn get_list(val: Value) -> List<Value> {
+  match val {
+    Value::List(xs) => xs,
+    _ => unreachable!(),
+  }
+}
+
+fn get_map(val: Value) -> Map<Value, Value> {
+  match val {
+    Value::Map(m) => m,
+    _ => unreachable!(),
+  }
+}
+
+fn get_set(val: Value) -> Set<Value> {
+  match val {
+    Value::Set(s) => s,
+    _ => unreachable!(),
+  }
+}
+
 fn mk_bool(b: bool) -> Value {
   Value::Ctor(
-    Ident::new(if b { "true" } else { "false" }),
-    Value::Tuple(vec![]).into(),
+    Ident::qualified(
+      &Ident::new(birb_std_lib::BOOL),
+      &Ident::new(if b { "true" } else { "false" }),
+    ),
+    vec![Value::Tuple(vec![])],
   )
 }
 
 fn get_bool(val: Value) -> bool {
   match val {
-    Value::Ctor(name, val) => {
-      assert_eq!(*val, Value::Tuple(vec![]));
+    Value::Ctor(name, vs) => {
+      assert_eq!(vs, [Value::Tuple(vec![])]);
+      let name = name.unqualified();
       if name == Ident::new("true") {
         return true;
       }
@@ -249,28 +654,20 @@ where
   Value::Number(f(x, y))
 }
 
-fn nat_cmp_op<F>(mut vs: Vec<Value>, f: F) -> Value
-where
-  F: FnOnce(u64, u64) -> bool,
-{
-  let y = get_number(vs.pop().unwrap());
-  let x = get_number(vs.pop().unwrap());
-  assert!(vs.is_empty());
-  mk_bool(f(x, y))
-}
-
-fn bool_op<F>(mut vs: Vec<Value>, f: F) -> Value
+/// The values must have the same type. Values of the intrinsic types are compared as expected, like
+/// numbers by size and strings lexicographically.
+fn cmp_op<F>(mut vs: Vec<Value>, f: F) -> Value
 where
-  F: FnOnce(bool, bool) -> bool,
+  F: FnOnce(&Value, &Value) -> bool,
 {
-  let y = get_bool(vs.pop().unwrap());
-  let x = get_bool(vs.pop().unwrap());
+  let y = vs.pop().unwrap();
+  let x = vs.pop().unwrap();
   assert!(vs.is_empty());
-  mk_bool(f(x, y))
+  mk_bool(f(&x, &y))
 }
 
 /// A value.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum Value {
   /// A string literal, like `"x"`.
   String_(String),
@@ -278,10 +675,18 @@ pub enum Value {
   Number(u64),
   /// A tuple, like `(1, "e")`.
   Tuple(Vec<Value>),
-  /// A struct expression, like `Foo { x: 3 }`.
+  /// A list, like `[1, 2, 3]`.
+  List(List<Value>),
+  /// A map, like `{1: "a", 2: "b"}`.
+  Map(Map<Value, Value>),
+  /// A set, like `{1, 2, 3}`.
+  Set(Set<Value>),
+  /// A struct, like `Foo { x: 3 }`, or a constructor with named fields, like `circle { radius: 3 }`.
+  /// The name of a constructor is qualified by its enum.
   Struct(Ident, Vec<Field<Value>>),
-  /// A constructor, like `some(3)`.
-  Ctor(Ident, Box<Value>),
+  /// A constructor, like `some(3)` or `none`. The name is qualified by the enum, like
+  /// `Option::some`.
+  Ctor(Ident, Vec<Value>),
 }
 
 impl fmt::Display for Value {
@@ -290,8 +695,42 @@ impl fmt::Display for Value {
       Self::String_(s) => write!(f, "\"{}\"", s),
       Self::Number(n) => n.fmt(f),
       Self::Tuple(vs) => SliceDisplay::new("(", vs, ")").fmt(f),
-      Self::Struct(name, fs) => write!(f, "{} {{ {} }}", name, SliceDisplay::new("", fs, ""),),
-      Self::Ctor(name, v) => write!(f, "{}({})", name, v),
+      Self::List(vs) => {
+        let vs: Vec<_> = vs.iter().collect();
+        SliceDisplay::new("[", &vs, "]").fmt(f)
+      }
+      Self::Map(m) => {
+        write!(f, "{{")?;
+        for (i, (k, v)) in m.iter().enumerate() {
+          if i != 0 {
+            write!(f, ", ")?;
+          }
+          write!(f, "{}: {}", k, v)?;
+        }
+        write!(f, "}}")
+      }
+      Self::Set(s) => {
+        let vs: Vec<_> = s.iter().collect();
+        SliceDisplay::new("{", &vs, "}").fmt(f)
+      }
+      Self::Struct(name, fs) => write!(
+        f,
+        "{} {{ {} }}",
+        name.unqualified(),
+        SliceDisplay::new("", fs, ""),
+      ),
+      Self::Ctor(name, vs) => {
+        if vs.is_empty() {
+          name.unqualified().fmt(f)
+        } else {
+          write!(
+            f,
+            "{}{}",
+            name.unqualified(),
+            SliceDisplay::new("(", vs, ")")
+          )
+        }
+      }
     }
   }
 }
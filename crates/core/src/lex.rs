@@ -30,10 +30,12 @@ pub fn get(bs: &[u8]) -> Result<Vec<Token>> {
       i += 1;
       continue;
     }
+    // an identifier may start with `_`, like `_x`, which is not the punctuation `_` then `x`.
+    let underscore_ident = bs[i] == b'_' && i + 1 < n && is_ident_tl(bs[i + 1]);
     // punctuation
     for &(tok_bs, ref tok) in PUNCT.iter() {
       let tok_n = tok_bs.len();
-      if i + tok_n <= n && bs[i..i + tok_n] == *tok_bs {
+      if !underscore_ident && i + tok_n <= n && bs[i..i + tok_n] == *tok_bs {
         ret.push(tok.clone());
         i += tok_n;
         continue 'outer;
@@ -54,7 +56,7 @@ pub fn get(bs: &[u8]) -> Result<Vec<Token>> {
       }
     }
     // identifier
-    if bs[i].is_ascii_lowercase() {
+    if bs[i].is_ascii_lowercase() || underscore_ident {
       let s = i;
       i += 1;
       while i < n && is_ident_tl(bs[i]) {
@@ -67,3 +67,57 @@ fn unterminated_string() {
 fn comment() {
   assert_eq!(get(b"3// hi\n4").unwrap(), vec![T::Number(3), T::Number(4)]);
 }
+
+#[test]
+fn dot_dot() {
+  assert_eq!(
+    get(b"[x, ..xs].len").unwrap(),
+    vec![
+      T::LSquare,
+      T::Ident(Ident::new("x")),
+      T::Comma,
+      T::DotDot,
+      T::Ident(Ident::new("xs")),
+      T::RSquare,
+      T::Dot,
+      T::Ident(Ident::new("len")),
+    ]
+  );
+}
+
+#[test]
+fn ops() {
+  assert_eq!(
+    get(b"a->b-c<=d<e==!f").unwrap(),
+    vec![
+      T::Ident(Ident::new("a")),
+      T::Arrow,
+      T::Ident(Ident::new("b")),
+      T::Minus,
+      T::Ident(Ident::new("c")),
+      T::LtEqual,
+      T::Ident(Ident::new("d")),
+      T::Lt,
+      T::Ident(Ident::new("e")),
+      T::EqualEqual,
+      T::Bang,
+      T::Ident(Ident::new("f")),
+    ]
+  );
+}
+
+#[test]
+fn bar_at() {
+  assert_eq!(
+    get(b"x@a|b||c").unwrap(),
+    vec![
+      T::Ident(Ident::new("x")),
+      T::At,
+      T::Ident(Ident::new("a")),
+      T::Bar,
+      T::Ident(Ident::new("b")),
+      T::BarBar,
+      T::Ident(Ident::new("c")),
+    ]
+  );
+}
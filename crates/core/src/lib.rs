@@ -11,31 +11,104 @@ pub mod error;
 pub mod ident;
 pub mod interpret;
 pub mod lex;
+pub mod lint;
+pub mod module;
 pub mod parse;
+pub mod persistent;
 pub mod statics;
 pub mod std_lib;
 pub mod token;
+pub mod warning;
 
 mod util;
 
-/// Lex, parse, typecheck, and evaluate a Birb program.
-pub fn get(bs: &[u8]) -> error::Result<interpret::Value> {
-  use std::collections::HashMap;
-  let ts = lex::get(bs)?;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Lex, parse, typecheck, and evaluate a Birb program, passing `args` to `main`. The program may
+/// not import other files. Returns the warnings too.
+pub fn get(bs: &[u8], args: &[String]) -> error::Result<(Vec<warning::Warning>, interpret::Value)> {
+  let no_files = |module: &ident::Ident| {
+    let e = std::io::Error::new(
+      std::io::ErrorKind::NotFound,
+      "cannot import without a main file",
+    );
+    Err(error::Error::Io(module.to_string().into(), e))
+  };
+  let program = module::get(bs, &Default::default(), no_files)?;
+  let (top_defns, info) = check(program, &warning::Levels::new())?;
+  Ok((info.warnings, run(top_defns, args)?))
+}
+
+/// Like `get`, but for the program whose main file is at `path`. Imports are read from the file
+/// system, relative to the directory of the main file.
+pub fn get_path(
+  path: &Path,
+  args: &[String],
+) -> error::Result<(Vec<warning::Warning>, interpret::Value)> {
+  let program = module::get_path(path, &Default::default())?;
+  let (top_defns, info) = check(program, &warning::Levels::new())?;
+  Ok((info.warnings, run(top_defns, args)?))
+}
+
+/// Add the prelude to the definitions of a loaded program, then elaborate, typecheck and lint them.
+/// Returns what the typecheck found out, with the warnings of the lints which are not allowed, or
+/// an error for the first warning of a denied lint.
+pub fn check(
+  mut program: module::Program,
+  levels: &warning::Levels,
+) -> error::Result<(Vec<cst::TopDefn>, statics::Info)> {
   let mut top_defns = std_lib::top_defns();
-  top_defns.append(&mut parse::get(&ts)?);
+  top_defns.append(&mut program.top_defns);
   let top_defns = elab::get(top_defns);
-  statics::get(&top_defns)?;
-  let cx: HashMap<_, _> = top_defns
-    .into_iter()
-    .map(|td| {
-      let name = match &td {
-        cst::TopDefn::Struct(defn) => defn.name.clone(),
-        cst::TopDefn::Enum(defn) => defn.name.clone(),
-        cst::TopDefn::Fn_(defn) => defn.name.clone(),
-      };
-      (name, td)
-    })
-    .collect();
-  interpret::get(cx)
+  let mut info = statics::get(&top_defns, &program.checked)?;
+  let mut warnings = program.warnings;
+  warnings.append(&mut info.warnings);
+  warnings.append(&mut lint::get(&top_defns));
+  for w in warnings {
+    let lint = w.lint();
+    match levels.get(&lint).copied().unwrap_or(lint.default_level()) {
+      warning::Level::Allow => {}
+      warning::Level::Warn => info.warnings.push(w),
+      warning::Level::Deny => return Err(error::Error::DeniedLint(w)),
+    }
+  }
+  Ok((top_defns, info))
+}
+
+/// Evaluate a checked program, passing `args` to `main`.
+pub fn run(top_defns: Vec<cst::TopDefn>, args: &[String]) -> error::Result<interpret::Value> {
+  let mut cx = HashMap::with_capacity(top_defns.len());
+  for td in top_defns {
+    let td = match td {
+      cst::TopDefn::Impl(defn) => {
+        add_impl_fns(&mut cx, defn);
+        continue;
+      }
+      td => td,
+    };
+    let name = match &td {
+      cst::TopDefn::Struct(defn) => defn.name.clone(),
+      cst::TopDefn::Enum(defn) => defn.name.clone(),
+      cst::TopDefn::Type(defn) => defn.name.clone(),
+      cst::TopDefn::Fn_(defn) => defn.name.clone(),
+      cst::TopDefn::Trait(defn) => defn.name.clone(),
+      cst::TopDefn::Impl(..) | cst::TopDefn::Import(..) => unreachable!(),
+    };
+    cx.insert(name, td);
+  }
+  interpret::get(cx, args)
+}
+
+/// The fns of impls are found by the interpreter from the trait and the type, like
+/// `<Nat as Eq>::eq`.
+fn add_impl_fns(cx: &mut HashMap<ident::Ident, cst::TopDefn>, defn: cst::ImplDefn) {
+  let type_ = match &defn.type_ {
+    cst::Kinded::Ident(name, _) => name,
+    _ => unreachable!(),
+  };
+  for f in defn.fns {
+    let name = ident::Ident::impl_fn(&defn.trait_, type_, &f.name.unqualified());
+    cx.insert(name, cst::TopDefn::Fn_(f.into()));
+  }
 }
@@ -0,0 +1,283 @@
+//! Lints for unused variables, big params and fns, and for shadowing. Runs after elaboration, on
+//! well-formed definitions.
+
+use crate::cst::{Block, Expr, Field, FnDefn, Kinded, Pat, Stmt, TopDefn};
+use crate::ident::Ident;
+use crate::warning::Warning;
+use std::collections::{HashMap, HashSet};
+
+/// Returns the warnings for the definitions. Names starting with `_` are never unused.
+pub fn get(top_defns: &[TopDefn]) -> Vec<Warning> {
+  let mut warnings = Vec::new();
+  // the fns which each fn calls, and the fns which are called no matter what.
+  let mut calls = HashMap::new();
+  let mut roots = Vec::new();
+  for td in top_defns {
+    match td {
+      TopDefn::Fn_(fn_) => {
+        let mut cx = lint_fn(fn_);
+        warnings.append(&mut cx.warnings);
+        if fn_.pub_ || fn_.name == Ident::new("main") {
+          roots.push(fn_.name.clone());
+        }
+        calls.insert(fn_.name.clone(), cx.calls);
+      }
+      TopDefn::Impl(impl_) => {
+        let type_name = match &impl_.type_ {
+          Kinded::Ident(name, _) => name,
+          _ => unreachable!("checked in statics"),
+        };
+        // impls are used through their traits, which we don't track.
+        for f in impl_.fns.iter() {
+          let mut cx = lint_fn(f);
+          warnings.append(&mut cx.warnings);
+          let name = Ident::impl_fn(&impl_.trait_, type_name, &f.name.unqualified());
+          roots.push(name.clone());
+          calls.insert(name, cx.calls);
+        }
+      }
+      TopDefn::Struct(..)
+      | TopDefn::Enum(..)
+      | TopDefn::Type(..)
+      | TopDefn::Trait(..)
+      | TopDefn::Import(..) => {}
+    }
+  }
+  let mut used = HashSet::new();
+  while let Some(name) = roots.pop() {
+    if !used.insert(name.clone()) {
+      continue;
+    }
+    if let Some(cs) = calls.get(&name) {
+      roots.extend(cs.iter().cloned());
+    }
+  }
+  for td in top_defns {
+    if let TopDefn::Fn_(fn_) = td {
+      if !used.contains(&fn_.name) && !is_ignored(&fn_.name.unqualified()) {
+        warnings.push(Warning::UnusedFn(fn_.name.clone()));
+      }
+    }
+  }
+  warnings
+}
+
+fn is_ignored(name: &Ident) -> bool {
+  name.to_string().starts_with('_')
+}
+
+struct Cx {
+  /// The fn being linted.
+  func: Ident,
+  warnings: Vec<Warning>,
+  /// The vars in scope, innermost last, with whether they were used and whether to warn if not.
+  vars: Vec<Var>,
+  /// The names used in types, which includes the big params.
+  big_used: HashSet<Ident>,
+  /// The names of the fns which might be called.
+  calls: HashSet<Ident>,
+}
+
+struct Var {
+  name: Ident,
+  used: bool,
+  lint: bool,
+}
+
+/// Lints the fn, returning its context after the lint.
+fn lint_fn(fn_: &FnDefn) -> Cx {
+  let mut cx = Cx {
+    func: fn_.name.clone(),
+    warnings: Vec::new(),
+    vars: Vec::new(),
+    big_used: HashSet::new(),
+    calls: HashSet::new(),
+  };
+  // intrinsic fns have no body to use their params.
+  let body = match &fn_.body {
+    Some(body) => body,
+    None => return cx,
+  };
+  for p in fn_.params.iter() {
+    use_kinded(&mut cx, &p.type_);
+    bind(&mut cx, p.ident.clone(), true);
+  }
+  use_kinded(&mut cx, &fn_.ret_type);
+  for b in fn_.bounds.iter() {
+    cx.big_used.insert(b.ident.clone());
+  }
+  for e in fn_.requires.iter().chain(fn_.ensures.iter()) {
+    lint_expr(&mut cx, e);
+  }
+  lint_block(&mut cx, body);
+  unbind(&mut cx, 0);
+  for p in fn_.big_params.iter() {
+    if !cx.big_used.contains(&p.ident) && !is_ignored(&p.ident) {
+      let w = Warning::UnusedBigParam(fn_.name.clone(), p.ident.clone());
+      cx.warnings.push(w);
+    }
+  }
+  cx
+}
+
+/// Binds the var, which shadows any var in scope with the same name until it goes out of scope.
+fn bind(cx: &mut Cx, name: Ident, lint: bool) {
+  if !is_ignored(&name) && cx.vars.iter().any(|v| v.name == name) {
+    cx.warnings
+      .push(Warning::Shadowed(cx.func.clone(), name.clone()));
+  }
+  cx.vars.push(Var {
+    name,
+    used: false,
+    lint,
+  });
+}
+
+/// Removes the vars bound since there were `len` vars, warning about the unused ones.
+fn unbind(cx: &mut Cx, len: usize) {
+  for var in cx.vars.drain(len..) {
+    if var.lint && !var.used && !is_ignored(&var.name) {
+      cx.warnings
+        .push(Warning::UnusedVar(cx.func.clone(), var.name));
+    }
+  }
+}
+
+fn use_var(cx: &mut Cx, name: &Ident) {
+  match cx.vars.iter_mut().rev().find(|v| v.name == *name) {
+    Some(var) => var.used = true,
+    // not a var, so maybe a fn used as a value.
+    None => {
+      cx.calls.insert(name.clone());
+    }
+  }
+}
+
+fn use_kinded(cx: &mut Cx, kinded: &Kinded) {
+  match kinded {
+    Kinded::Ident(name, args) => {
+      cx.big_used.insert(name.clone());
+      for a in args {
+        use_kinded(cx, a);
+      }
+    }
+    Kinded::Tuple(ks) | Kinded::Set(ks) => {
+      for k in ks {
+        use_kinded(cx, k);
+      }
+    }
+    Kinded::Arrow(k1, k2) | Kinded::Effectful(k1, k2) => {
+      use_kinded(cx, k1);
+      use_kinded(cx, k2);
+    }
+    Kinded::Never => {}
+  }
+}
+
+fn lint_expr(cx: &mut Cx, expr: &Expr) {
+  match expr {
+    Expr::String_(..) | Expr::Number(..) => {}
+    Expr::Tuple(es) | Expr::List(es) => {
+      for e in es {
+        lint_expr(cx, e);
+      }
+    }
+    Expr::Struct(_, args, fields, base) => {
+      for a in args {
+        use_kinded(cx, a);
+      }
+      for f in fields {
+        match f {
+          Field::Ident(x) => use_var(cx, x),
+          Field::IdentAnd(_, e) => lint_expr(cx, e),
+        }
+      }
+      if let Some(base) = base {
+        lint_expr(cx, base);
+      }
+    }
+    Expr::Ident(x) => use_var(cx, x),
+    Expr::FnCall(name, big_args, args) => {
+      cx.calls.insert(name.clone());
+      for a in big_args {
+        use_kinded(cx, a);
+      }
+      for e in args {
+        lint_expr(cx, e);
+      }
+    }
+    Expr::FieldGet(e, _) | Expr::Return(e) => lint_expr(cx, e),
+    Expr::Match(head, arms) => {
+      lint_expr(cx, head);
+      for arm in arms {
+        let len = cx.vars.len();
+        bind_pat(cx, &arm.pat, false);
+        if let Some(guard) = &arm.guard {
+          lint_expr(cx, guard);
+        }
+        lint_block(cx, &arm.block);
+        unbind(cx, len);
+      }
+    }
+    Expr::Block(blk) => lint_block(cx, blk),
+    Expr::MethodCall(..) => unreachable!("lint method call"),
+    Expr::BinOp(..) => unreachable!("lint bin op"),
+    Expr::Not(..) => unreachable!("lint not"),
+    Expr::If(..) => unreachable!("lint if"),
+  }
+}
+
+fn lint_block(cx: &mut Cx, blk: &Block) {
+  let len = cx.vars.len();
+  for stmt in blk.stmts.iter() {
+    match stmt {
+      Stmt::Let(pat, typ, e) => {
+        lint_expr(cx, e);
+        if let Some(typ) = typ {
+          use_kinded(cx, typ);
+        }
+        bind_pat(cx, pat, true);
+      }
+    }
+  }
+  if let Some(e) = &blk.expr {
+    lint_expr(cx, e);
+  }
+  unbind(cx, len);
+}
+
+/// Binds the vars of the pattern. Only the vars of the first alternative of an or-pattern are
+/// bound, since every alternative binds the same vars.
+fn bind_pat(cx: &mut Cx, pat: &Pat, lint: bool) {
+  match pat {
+    Pat::Wildcard | Pat::String_(..) | Pat::Number(..) => {}
+    Pat::Tuple(pats) | Pat::Ctor(_, pats) => {
+      for p in pats {
+        bind_pat(cx, p, lint);
+      }
+    }
+    Pat::List(pats, rest) => {
+      for p in pats.iter().chain(rest.as_deref()) {
+        bind_pat(cx, p, lint);
+      }
+    }
+    Pat::Ident(x) => bind(cx, x.clone(), lint),
+    Pat::Struct(_, fields, _) => {
+      for f in fields {
+        match f {
+          Field::Ident(x) => bind(cx, x.clone(), lint),
+          Field::IdentAnd(_, p) => bind_pat(cx, p, lint),
+        }
+      }
+    }
+    Pat::Or(pats) => {
+      if let Some(p) = pats.first() {
+        bind_pat(cx, p, lint);
+      }
+    }
+    Pat::At(x, p) => {
+      bind(cx, x.clone(), lint);
+      bind_pat(cx, p, lint);
+    }
+  }
+}
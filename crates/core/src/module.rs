@@ -0,0 +1,449 @@
+//! Loading modules. Every imported file is a module, and the top-level definitions in it are
+//! renamed to be qualified by the path to the module, like `lib::strings::concat` for `concat` in
+//! `lib/strings.birb`. The result is the definitions from every module, with no imports, in an
+//! order such that each module comes after the modules it imports, and warnings for the unused
+//! imports.
+//!
+//! Modules in packages are instead qualified by the name of the package, like `geo::area` for
+//! `area` in the main file of the `geo` package, and `geo::shapes::area` for `area` in
+//! `shapes.birb` next to it.
+
+use crate::cst::{Arm, Block, Expr, Field, FnDefn, Kinded, Param, Pat, Payload, Stmt, TopDefn};
+use crate::error::{Error, Result};
+use crate::ident::Ident;
+use crate::warning::Warning;
+use crate::{lex, parse};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A loaded program.
+#[derive(Debug, Default)]
+pub struct Program {
+  /// The definitions from every module.
+  pub top_defns: Vec<TopDefn>,
+  /// The warnings found while loading.
+  pub warnings: Vec<Warning>,
+  /// The packages which were checked before and have not changed since. The bodies of their
+  /// definitions are not checked again, which is fine since other packages see only their
+  /// signatures.
+  pub checked: HashSet<Ident>,
+}
+
+/// The packages of a program other than the main one. Every package, including the main one, may
+/// import only the packages it depends on directly.
+#[derive(Debug, Default)]
+pub struct Packages {
+  /// The packages the main package depends on.
+  pub main_deps: HashSet<Ident>,
+  /// The packages each package depends on, by name.
+  pub deps: HashMap<Ident, HashSet<Ident>>,
+  /// The path to the main file of each package, by name.
+  pub paths: HashMap<Ident, PathBuf>,
+}
+
+/// Loads the program whose main file has the given contents and which may import the given
+/// packages. Imported modules are read with `read`, which is given the name of the module.
+pub fn get<F>(bs: &[u8], packages: &Packages, read: F) -> Result<Program>
+where
+  F: FnMut(&Ident) -> Result<Vec<u8>>,
+{
+  let mut ld = Loader {
+    read,
+    packages,
+    done: HashSet::new(),
+    stack: Vec::new(),
+    ret: Program::default(),
+  };
+  ld.load(None, bs)?;
+  Ok(ld.ret)
+}
+
+/// Loads the program whose main file is at `main` from the file system. Modules are read relative
+/// to the directory of the main file, except for modules in the given packages, which are read
+/// relative to the directory of the main file of the package.
+pub fn get_path(main: &Path, packages: &Packages) -> Result<Program> {
+  let read = |path: &Path| std::fs::read(path).map_err(|e| Error::Io(path.to_owned(), e));
+  let bs = read(main)?;
+  let paths = &packages.paths;
+  get(&bs, packages, |module| {
+    let (main, rest) = match module.split_first() {
+      Some((first, rest)) if paths.contains_key(&first) => (paths[&first].as_path(), Some(rest)),
+      None if paths.contains_key(module) => (paths[module].as_path(), None),
+      _ => (main, Some(module.clone())),
+    };
+    match rest {
+      None => read(main),
+      Some(rest) => {
+        let dir = main.parent().unwrap_or_else(|| Path::new(""));
+        let mut path: PathBuf = dir.join(rest.to_string().split("::").collect::<PathBuf>());
+        path.set_extension("birb");
+        read(&path)
+      }
+    }
+  })
+}
+
+struct Loader<'a, F> {
+  read: F,
+  packages: &'a Packages,
+  /// The modules which have been completely loaded.
+  done: HashSet<Ident>,
+  /// The modules currently being loaded, used to detect cycles.
+  stack: Vec<Ident>,
+  ret: Program,
+}
+
+impl<F> Loader<'_, F>
+where
+  F: FnMut(&Ident) -> Result<Vec<u8>>,
+{
+  /// Loads the module with the given name (None for the main file) and contents.
+  fn load(&mut self, module: Option<&Ident>, bs: &[u8]) -> Result<()> {
+    let ts = lex::get(bs)?;
+    let packages = self.packages;
+    // imports are relative to the directory containing this module. the main file of a package is
+    // like a directory containing the other modules in the package.
+    let dir = match module {
+      None => None,
+      Some(m) if packages.deps.contains_key(m) => Some(m.clone()),
+      Some(m) => m.split().map(|(dir, _)| dir),
+    };
+    // the package this module is in, if any. modules next to the main file are in the main package.
+    let package = module.map(|m| match m.split_first() {
+      Some((first, _)) => first,
+      None => m.clone(),
+    });
+    let deps = match package.and_then(|p| packages.deps.get(&p)) {
+      Some(deps) => deps,
+      None => &packages.main_deps,
+    };
+    let mut imports = HashMap::new();
+    let mut top_defns = Vec::new();
+    for td in parse::get(&ts)? {
+      let path = match td {
+        TopDefn::Import(path) => path,
+        _ => {
+          top_defns.push(td);
+          continue;
+        }
+      };
+      let mut iter = path.iter();
+      let first = iter.next().unwrap();
+      let mut name = match &dir {
+        _ if deps.contains(first) => first.clone(),
+        Some(dir) => Ident::qualified(dir, first),
+        // a module next to the main file would have the same name as the package.
+        None if packages.deps.contains_key(first) => {
+          return Err(Error::NotDependency(first.clone()))
+        }
+        None => first.clone(),
+      };
+      for p in iter {
+        name = Ident::qualified(&name, p);
+      }
+      let alias = path.last().unwrap().clone();
+      if imports.insert(alias.clone(), name.clone()).is_some() {
+        return Err(Error::DuplicateIdentifier(alias));
+      }
+      self.import(name)?;
+    }
+    let mut locals = HashSet::with_capacity(top_defns.len());
+    for td in top_defns.iter() {
+      let names: Vec<_> = match td {
+        TopDefn::Struct(defn) => vec![&defn.name],
+        TopDefn::Enum(defn) => vec![&defn.name],
+        TopDefn::Type(defn) => vec![&defn.name],
+        TopDefn::Fn_(defn) => vec![&defn.name],
+        // the fns of a trait are called like top-level fns.
+        TopDefn::Trait(defn) => std::iter::once(&defn.name)
+          .chain(defn.fns.iter().map(|f| &f.name))
+          .collect(),
+        TopDefn::Impl(..) => vec![],
+        TopDefn::Import(..) => unreachable!(),
+      };
+      for name in names {
+        if imports.contains_key(name) {
+          return Err(Error::DuplicateIdentifier(name.clone()));
+        }
+        locals.insert(name.clone());
+      }
+    }
+    let scope = Scope {
+      module,
+      locals,
+      imports,
+      used: RefCell::new(HashSet::new()),
+    };
+    self
+      .ret
+      .top_defns
+      .extend(top_defns.into_iter().map(|td| scope.top_defn(td)));
+    let used = scope.used.into_inner();
+    let mut unused: Vec<_> = scope
+      .imports
+      .into_iter()
+      .filter(|(alias, _)| !used.contains(alias))
+      .map(|(_, name)| name)
+      .collect();
+    unused.sort();
+    self
+      .ret
+      .warnings
+      .extend(unused.into_iter().map(Warning::UnusedImport));
+    Ok(())
+  }
+
+  fn import(&mut self, name: Ident) -> Result<()> {
+    if let Some(idx) = self.stack.iter().position(|m| *m == name) {
+      let mut cycle = self.stack[idx..].to_vec();
+      cycle.push(name);
+      return Err(Error::CyclicImport(cycle));
+    }
+    if self.done.contains(&name) {
+      return Ok(());
+    }
+    let bs = (self.read)(&name)?;
+    self.stack.push(name.clone());
+    self.load(Some(&name), &bs)?;
+    self.stack.pop();
+    self.done.insert(name);
+    Ok(())
+  }
+}
+
+/// The names visible in a module.
+struct Scope<'a> {
+  /// None for the main file.
+  module: Option<&'a Ident>,
+  /// The top-level definitions of the module.
+  locals: HashSet<Ident>,
+  /// The modules imported by this module, by the name they are imported as.
+  imports: HashMap<Ident, Ident>,
+  /// The names of the imports which have been used.
+  used: RefCell<HashSet<Ident>>,
+}
+
+impl Scope<'_> {
+  fn qualify(&self, id: Ident) -> Ident {
+    match self.module {
+      None => id,
+      Some(m) => Ident::qualified(m, &id),
+    }
+  }
+
+  /// Resolves a name written in this module. Names which are neither defined in this module nor
+  /// qualified by an imported module are left alone, since they may be from the prelude.
+  fn resolve(&self, id: Ident) -> Ident {
+    match id.split_first() {
+      None => {
+        if self.locals.contains(&id) {
+          self.qualify(id)
+        } else {
+          id
+        }
+      }
+      Some((first, rest)) => {
+        if let Some(m) = self.imports.get(&first) {
+          self.used.borrow_mut().insert(first);
+          Ident::qualified(m, &rest)
+        } else if self.locals.contains(&first) {
+          self.qualify(id)
+        } else {
+          id
+        }
+      }
+    }
+  }
+
+  fn top_defn(&self, td: TopDefn) -> TopDefn {
+    match td {
+      TopDefn::Struct(mut defn) => {
+        defn.name = self.qualify(defn.name);
+        defn.fields = self.params(defn.fields);
+        TopDefn::Struct(defn)
+      }
+      TopDefn::Enum(mut defn) => {
+        defn.name = self.qualify(defn.name);
+        defn.ctors = defn
+          .ctors
+          .into_iter()
+          .map(|p| Param {
+            ident: p.ident,
+            type_: match p.type_ {
+              Payload::Positional(ts) => Payload::Positional(self.kindeds(ts)),
+              Payload::Named(fields) => Payload::Named(self.params(fields)),
+            },
+          })
+          .collect();
+        TopDefn::Enum(defn)
+      }
+      TopDefn::Type(mut defn) => {
+        defn.name = self.qualify(defn.name);
+        defn.def = defn.def.map(|k| self.kinded(k));
+        TopDefn::Type(defn)
+      }
+      TopDefn::Fn_(defn) => TopDefn::Fn_(self.fn_(*defn).into()),
+      TopDefn::Trait(mut defn) => {
+        defn.name = self.qualify(defn.name);
+        defn.fns = defn.fns.into_iter().map(|f| self.fn_(f)).collect();
+        TopDefn::Trait(defn)
+      }
+      TopDefn::Impl(mut defn) => {
+        defn.bounds = self.bounds(defn.bounds);
+        defn.trait_ = self.resolve(defn.trait_);
+        defn.type_ = self.kinded(defn.type_);
+        // the names of the fns stay qualified by this module, so they can use its private items.
+        defn.fns = defn.fns.into_iter().map(|f| self.fn_(f)).collect();
+        TopDefn::Impl(defn)
+      }
+      TopDefn::Import(..) => unreachable!(),
+    }
+  }
+
+  fn fn_(&self, defn: FnDefn) -> FnDefn {
+    FnDefn {
+      name: self.qualify(defn.name),
+      bounds: self.bounds(defn.bounds),
+      params: self.params(defn.params),
+      ret_type: self.kinded(defn.ret_type),
+      requires: defn.requires.map(|e| self.expr(e)),
+      ensures: defn.ensures.map(|e| self.expr(e)),
+      body: defn.body.map(|b| self.block(b)),
+      ..defn
+    }
+  }
+
+  fn bounds(&self, bs: Vec<Param<Ident, Ident>>) -> Vec<Param<Ident, Ident>> {
+    bs.into_iter()
+      .map(|b| Param {
+        ident: b.ident,
+        type_: self.resolve(b.type_),
+      })
+      .collect()
+  }
+
+  fn params(&self, ps: Vec<Param<Ident, Kinded>>) -> Vec<Param<Ident, Kinded>> {
+    ps.into_iter()
+      .map(|p| Param {
+        ident: p.ident,
+        type_: self.kinded(p.type_),
+      })
+      .collect()
+  }
+
+  fn kinded(&self, kinded: Kinded) -> Kinded {
+    match kinded {
+      Kinded::Ident(name, args) => Kinded::Ident(self.resolve(name), self.kindeds(args)),
+      Kinded::Tuple(ts) => Kinded::Tuple(self.kindeds(ts)),
+      Kinded::Set(es) => Kinded::Set(self.kindeds(es)),
+      Kinded::Arrow(t1, t2) => Kinded::Arrow(self.kinded(*t1).into(), self.kinded(*t2).into()),
+      Kinded::Effectful(t, e) => Kinded::Effectful(self.kinded(*t).into(), self.kinded(*e).into()),
+      Kinded::Never => Kinded::Never,
+    }
+  }
+
+  fn kindeds(&self, ks: Vec<Kinded>) -> Vec<Kinded> {
+    ks.into_iter().map(|k| self.kinded(k)).collect()
+  }
+
+  fn expr(&self, expr: Expr) -> Expr {
+    match expr {
+      Expr::String_(..) | Expr::Number(..) => expr,
+      Expr::Tuple(es) => Expr::Tuple(self.exprs(es)),
+      Expr::List(es) => Expr::List(self.exprs(es)),
+      Expr::Struct(name, args, fields, base) => Expr::Struct(
+        self.resolve(name),
+        self.kindeds(args),
+        fields
+          .into_iter()
+          .map(|f| match f {
+            Field::Ident(..) => f,
+            Field::IdentAnd(id, e) => Field::IdentAnd(id, self.expr(e)),
+          })
+          .collect(),
+        base.map(|e| self.expr(*e).into()),
+      ),
+      // only qualified names could refer to anything other than a variable.
+      Expr::Ident(id) => match id.split() {
+        None => Expr::Ident(id),
+        Some(_) => Expr::Ident(self.resolve(id)),
+      },
+      Expr::FnCall(name, big_args, args) => {
+        Expr::FnCall(self.resolve(name), self.kindeds(big_args), self.exprs(args))
+      }
+      Expr::FieldGet(e, field) => Expr::FieldGet(self.expr(*e).into(), field),
+      Expr::MethodCall(recv, name, big_args, args) => Expr::MethodCall(
+        self.expr(*recv).into(),
+        self.resolve(name),
+        self.kindeds(big_args),
+        self.exprs(args),
+      ),
+      Expr::BinOp(lhs, op, rhs) => Expr::BinOp(self.expr(*lhs).into(), op, self.expr(*rhs).into()),
+      Expr::Not(e) => Expr::Not(self.expr(*e).into()),
+      Expr::Match(head, arms) => Expr::Match(
+        self.expr(*head).into(),
+        arms
+          .into_iter()
+          .map(|arm| Arm {
+            pat: self.pat(arm.pat),
+            guard: arm.guard.map(|e| self.expr(e)),
+            block: self.block(arm.block),
+          })
+          .collect(),
+      ),
+      Expr::If(cond, yes, no) => Expr::If(
+        self.expr(*cond).into(),
+        self.block(*yes).into(),
+        self.block(*no).into(),
+      ),
+      Expr::Block(b) => Expr::Block(self.block(*b).into()),
+      Expr::Return(e) => Expr::Return(self.expr(*e).into()),
+    }
+  }
+
+  fn exprs(&self, es: Vec<Expr>) -> Vec<Expr> {
+    es.into_iter().map(|e| self.expr(e)).collect()
+  }
+
+  fn pat(&self, pat: Pat) -> Pat {
+    match pat {
+      Pat::Wildcard | Pat::String_(..) | Pat::Number(..) | Pat::Ident(..) => pat,
+      Pat::Tuple(ps) => Pat::Tuple(self.pats(ps)),
+      Pat::List(ps, rest) => Pat::List(self.pats(ps), rest.map(|p| self.pat(*p).into())),
+      Pat::Struct(name, fields, rest) => Pat::Struct(
+        self.resolve(name),
+        fields
+          .into_iter()
+          .map(|f| match f {
+            Field::Ident(..) => f,
+            Field::IdentAnd(id, p) => Field::IdentAnd(id, self.pat(p)),
+          })
+          .collect(),
+        rest,
+      ),
+      Pat::Ctor(name, ps) => Pat::Ctor(self.resolve(name), self.pats(ps)),
+      Pat::Or(ps) => Pat::Or(self.pats(ps)),
+      Pat::At(name, p) => Pat::At(name, self.pat(*p).into()),
+    }
+  }
+
+  fn pats(&self, ps: Vec<Pat>) -> Vec<Pat> {
+    ps.into_iter().map(|p| self.pat(p)).collect()
+  }
+
+  fn block(&self, block: Block) -> Block {
+    Block {
+      stmts: block
+        .stmts
+        .into_iter()
+        .map(|stmt| match stmt {
+          Stmt::Let(pat, typ, e) => {
+            Stmt::Let(self.pat(pat), typ.map(|t| self.kinded(t)), self.expr(e))
+          }
+        })
+        .collect(),
+      expr: block.expr.map(|e| self.expr(e)),
+    }
+  }
+}
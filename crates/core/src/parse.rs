@@ -1,7 +1,8 @@
 //! Parsing.
 
 use crate::cst::{
-  Arm, Block, EnumDefn, Expr, Field, FnDefn, Kind, Kinded, Param, Pat, Stmt, StructDefn, TopDefn,
+  Arm, BinOp, Block, EnumDefn, Expr, Field, FnDefn, ImplDefn, Kind, Kinded, Param, Pat, Payload,
+  Stmt, StructDefn, TopDefn, TraitDefn, TypeDefn,
 };
 use crate::error::{Error, Result};
 use crate::ident::Ident;
@@ -41,6 +42,21 @@ impl fmt::Display for Found {
 // grammar items
 
 fn top_defn(i: usize, ts: &[Token]) -> Result<(usize, TopDefn)> {
+  if let Ok(i) = eat(i, ts, Token::Import) {
+    let (mut i, id) = ident(i, ts)?;
+    let mut path = vec![id];
+    while let Ok(j) = eat(i, ts, Token::ColonColon) {
+      let (j, id) = ident(j, ts)?;
+      path.push(id);
+      i = j;
+    }
+    let i = eat(i, ts, Token::Semi)?;
+    return Ok((i, TopDefn::Import(path)));
+  }
+  let (i, pub_) = match eat(i, ts, Token::Pub) {
+    Ok(i) => (i, true),
+    Err(_) => (i, false),
+  };
   if let Ok(i) = eat(i, ts, Token::Struct) {
     let (i, name) = big_ident(i, ts)?;
     let (i, params) = big_param_list_opt(i, ts)?;
@@ -51,6 +67,7 @@ fn top_defn(i: usize, ts: &[Token]) -> Result<(usize, TopDefn)> {
       i,
       TopDefn::Struct(StructDefn {
         name,
+        pub_,
         params,
         fields,
       }),
@@ -66,39 +83,114 @@ fn top_defn(i: usize, ts: &[Token]) -> Result<(usize, TopDefn)> {
       i,
       TopDefn::Enum(EnumDefn {
         name,
+        pub_,
         params,
         ctors,
       }),
     ));
   }
-  if let Ok(i) = eat(i, ts, Token::Fn_) {
-    let (i, name) = ident(i, ts)?;
-    let (i, big_params) = big_param_list_opt(i, ts)?;
-    let i = eat(i, ts, Token::LRound)?;
-    let (i, params) = comma_sep(i, ts, param)?;
-    let i = eat(i, ts, Token::RRound)?;
-    let i = eat(i, ts, Token::Colon)?;
-    let (i, ret_type) = kinded(i, ts)?;
-    let (i, requires) = requires_clause(i, ts)?;
-    let (i, ensures) = ensures_clause(i, ts)?;
-    let (i, body) = block(i, ts)?;
+  if let Ok(i) = eat(i, ts, Token::Type) {
+    let (i, name) = big_ident(i, ts)?;
+    let (i, params) = big_param_list_opt(i, ts)?;
+    let (i, def) = match eat(i, ts, Token::Equal) {
+      Ok(i) => {
+        let (i, def) = kinded(i, ts)?;
+        (i, Some(def))
+      }
+      Err(_) => (i, None),
+    };
+    let i = eat(i, ts, Token::Semi)?;
     return Ok((
       i,
-      TopDefn::Fn_(Box::new(FnDefn {
+      TopDefn::Type(TypeDefn {
         name,
-        big_params,
+        pub_,
         params,
-        ret_type,
-        requires,
-        ensures,
-        body,
-      })),
+        def,
+      }),
+    ));
+  }
+  if let Ok(i) = eat(i, ts, Token::Fn_) {
+    let (i, f) = fn_tl(i, ts, pub_)?;
+    return Ok((i, TopDefn::Fn_(Box::new(f))));
+  }
+  if let Ok(i) = eat(i, ts, Token::Trait) {
+    let (i, name) = big_ident(i, ts)?;
+    let i = eat(i, ts, Token::LCurly)?;
+    let (i, fns) = fn_list(i, ts)?;
+    let i = eat(i, ts, Token::RCurly)?;
+    return Ok((i, TopDefn::Trait(TraitDefn { name, pub_, fns })));
+  }
+  if let Ok(i) = eat(i, ts, Token::Impl) {
+    if pub_ {
+      // impls are always visible wherever the trait and type are.
+      return err(i - 1, ts, "a top-level definition");
+    }
+    let (i, big_params, bounds) = bounded_big_param_list_opt(i, ts)?;
+    let (i, trait_) = big_name(i, ts)?;
+    let i = eat(i, ts, Token::For)?;
+    let (i, type_) = kinded(i, ts)?;
+    let i = eat(i, ts, Token::LCurly)?;
+    let (i, fns) = fn_list(i, ts)?;
+    let i = eat(i, ts, Token::RCurly)?;
+    return Ok((
+      i,
+      TopDefn::Impl(ImplDefn {
+        big_params,
+        bounds,
+        trait_,
+        type_,
+        fns,
+      }),
     ));
   }
   err(i, ts, "a top-level definition")
 }
 
-fn big_param_list_opt(i: usize, ts: &[Token]) -> Result<(usize, Vec<Param<Ident, Kind>>)> {
+fn fn_list(mut i: usize, ts: &[Token]) -> Result<(usize, Vec<FnDefn>)> {
+  let mut ret = Vec::new();
+  while let Ok(j) = eat(i, ts, Token::Fn_) {
+    let (j, f) = fn_tl(j, ts, false)?;
+    ret.push(f);
+    i = j;
+  }
+  Ok((i, ret))
+}
+
+fn fn_tl(i: usize, ts: &[Token], pub_: bool) -> Result<(usize, FnDefn)> {
+  let (i, name) = ident(i, ts)?;
+  let (i, big_params, bounds) = bounded_big_param_list_opt(i, ts)?;
+  let i = eat(i, ts, Token::LRound)?;
+  let (i, params) = comma_sep(i, ts, param)?;
+  let i = eat(i, ts, Token::RRound)?;
+  let i = eat(i, ts, Token::Colon)?;
+  let (i, ret_type) = kinded(i, ts)?;
+  let (i, requires) = requires_clause(i, ts)?;
+  let (i, ensures) = ensures_clause(i, ts)?;
+  let (i, body) = match eat(i, ts, Token::Semi) {
+    Ok(i) => (i, None),
+    Err(_) => {
+      let (i, b) = block(i, ts)?;
+      (i, Some(b))
+    }
+  };
+  Ok((
+    i,
+    FnDefn {
+      name,
+      pub_,
+      big_params,
+      bounds,
+      params,
+      ret_type,
+      requires,
+      ensures,
+      body,
+    },
+  ))
+}
+
+fn big_param_list_opt(i: usize, ts: &[Token]) -> Result<(usize, BigParams)> {
   let i = match eat(i, ts, Token::LSquare) {
     Ok(i) => i,
     Err(_) => return Ok((i, Vec::new())),
@@ -112,10 +204,76 @@ fn big_param_list_opt(i: usize, ts: &[Token]) -> Result<(usize, Vec<Param<Ident,
   }
 }
 
-fn big_param(i: usize, ts: &[Token]) -> Result<(usize, Param<Ident, Kind>)> {
+type BigParams = Vec<Param<Ident, Option<Kind>>>;
+
+type Bounds = Vec<Param<Ident, Ident>>;
+
+type Bounded = (Param<Ident, Option<Kind>>, Bounds);
+
+/// Like `big_param_list_opt`, but the params may also have trait bounds, like `[T: Type + Eq]` or
+/// `[T: Eq]`.
+fn bounded_big_param_list_opt(i: usize, ts: &[Token]) -> Result<(usize, BigParams, Bounds)> {
+  let i = match eat(i, ts, Token::LSquare) {
+    Ok(i) => i,
+    Err(_) => return Ok((i, Vec::new(), Vec::new())),
+  };
+  let (i, ps) = comma_sep(i, ts, bounded_big_param)?;
+  let i = eat(i, ts, Token::RSquare)?;
+  if ps.is_empty() {
+    return Err(Error::EmptyKindedParams);
+  }
+  let mut params = Vec::with_capacity(ps.len());
+  let mut bounds = Vec::new();
+  for (p, bs) in ps {
+    params.push(p);
+    bounds.extend(bs);
+  }
+  Ok((i, params, bounds))
+}
+
+fn bounded_big_param(i: usize, ts: &[Token]) -> Result<(usize, Bounded)> {
   let (i, bi) = big_ident(i, ts)?;
-  let i = eat(i, ts, Token::Colon)?;
-  let (i, k) = kind(i, ts)?;
+  let mut traits = Vec::new();
+  let (mut i, k) = match eat(i, ts, Token::Colon) {
+    Err(_) => (i, None),
+    Ok(i) => match kind(i, ts) {
+      Ok((i, k)) => (i, Some(k)),
+      // no kind, only bounds.
+      Err(_) => {
+        let (i, trait_) = big_name(i, ts)?;
+        traits.push(trait_);
+        (i, None)
+      }
+    },
+  };
+  while let Ok(j) = eat(i, ts, Token::Plus) {
+    let (j, trait_) = big_name(j, ts)?;
+    traits.push(trait_);
+    i = j;
+  }
+  let bounds = traits
+    .into_iter()
+    .map(|trait_| Param {
+      ident: bi.clone(),
+      type_: trait_,
+    })
+    .collect();
+  let p = Param {
+    ident: bi,
+    type_: k,
+  };
+  Ok((i, (p, bounds)))
+}
+
+fn big_param(i: usize, ts: &[Token]) -> Result<(usize, Param<Ident, Option<Kind>>)> {
+  let (i, bi) = big_ident(i, ts)?;
+  let (i, k) = match eat(i, ts, Token::Colon) {
+    Ok(i) => {
+      let (i, k) = kind(i, ts)?;
+      (i, Some(k))
+    }
+    Err(_) => (i, None),
+  };
   Ok((
     i,
     Param {
@@ -171,8 +329,11 @@ fn kinded(i: usize, ts: &[Token]) -> Result<(usize, Kinded)> {
 }
 
 fn kinded_hd(i: usize, ts: &[Token]) -> Result<(usize, Kinded)> {
-  if let Ok((i, bi)) = big_ident(i, ts) {
-    let (i, args, _) = kinded_args_opt(i, ts)?;
+  if let Ok((j, bi, big)) = name(i, ts) {
+    if !big {
+      return err(i, ts, "a type or effect");
+    }
+    let (i, args, _) = kinded_args_opt(j, ts)?;
     return Ok((i, Kinded::Ident(bi, args)));
   }
   if let Ok(i) = eat(i, ts, Token::LRound) {
@@ -212,16 +373,24 @@ fn kinded_args_opt(i: usize, ts: &[Token]) -> Result<(usize, Vec<Kinded>, bool)>
   }
 }
 
-fn ctor(i: usize, ts: &[Token]) -> Result<(usize, Param<Ident, Kinded>)> {
+fn ctor(i: usize, ts: &[Token]) -> Result<(usize, Param<Ident, Payload>)> {
   let (i, id) = ident(i, ts)?;
-  let i = eat(i, ts, Token::LRound)?;
-  let (i, t) = kinded(i, ts)?;
-  let i = eat(i, ts, Token::RRound)?;
+  let (i, payload) = if let Ok(i) = eat(i, ts, Token::LRound) {
+    let (i, ts_) = comma_sep(i, ts, kinded)?;
+    let i = eat(i, ts, Token::RRound)?;
+    (i, Payload::Positional(ts_))
+  } else if let Ok(i) = eat(i, ts, Token::LCurly) {
+    let (i, fields) = comma_sep(i, ts, param)?;
+    let i = eat(i, ts, Token::RCurly)?;
+    (i, Payload::Named(fields))
+  } else {
+    (i, Payload::Positional(Vec::new()))
+  };
   Ok((
     i,
     Param {
       ident: id,
-      type_: t,
+      type_: payload,
     },
   ))
 }
@@ -244,7 +413,7 @@ fn requires_clause(i: usize, ts: &[Token]) -> Result<(usize, Option<Expr>)> {
     Ok(i) => i,
     Err(_) => return Ok((i, None)),
   };
-  let (i, e) = expr(i, ts)?;
+  let (i, e) = expr_no_struct(i, ts)?;
   Ok((i, Some(e)))
 }
 
@@ -253,7 +422,7 @@ fn ensures_clause(i: usize, ts: &[Token]) -> Result<(usize, Option<Expr>)> {
     Ok(i) => i,
     Err(_) => return Ok((i, None)),
   };
-  let (i, e) = expr(i, ts)?;
+  let (i, e) = expr_no_struct(i, ts)?;
   Ok((i, Some(e)))
 }
 
@@ -292,6 +461,49 @@ fn type_annotation(i: usize, ts: &[Token]) -> Result<(usize, Option<Kinded>)> {
 }
 
 fn pat(i: usize, ts: &[Token]) -> Result<(usize, Pat)> {
+  pat_or(i, ts, false)
+}
+
+/// Parses the pattern of a match arm. An ident pattern followed by the block of the arm looks like
+/// a ctor with named fields, like `circle { radius }`, so that is only allowed if the guard or block
+/// of the arm comes after it.
+fn arm_pat(i: usize, ts: &[Token]) -> Result<(usize, Pat)> {
+  if let Ok((j, p)) = pat_or(i, ts, false) {
+    if eat(j, ts, Token::If).is_ok() || eat(j, ts, Token::LCurly).is_ok() {
+      return Ok((j, p));
+    }
+  }
+  pat_or(i, ts, true)
+}
+
+fn pat_or(i: usize, ts: &[Token], no_struct: bool) -> Result<(usize, Pat)> {
+  let (mut i, p) = pat_at(i, ts, no_struct)?;
+  let mut pats = vec![p];
+  while let Ok(j) = eat(i, ts, Token::Bar) {
+    let (j, p) = pat_at(j, ts, no_struct)?;
+    pats.push(p);
+    i = j;
+  }
+  let p = if pats.len() == 1 {
+    pats.pop().unwrap()
+  } else {
+    Pat::Or(pats)
+  };
+  Ok((i, p))
+}
+
+/// Parses a pattern which may be an as-binding, but not an or-pattern.
+fn pat_at(i: usize, ts: &[Token], no_struct: bool) -> Result<(usize, Pat)> {
+  if let Ok((i, id)) = ident(i, ts) {
+    if let Ok(i) = eat(i, ts, Token::At) {
+      let (i, p) = pat_at(i, ts, no_struct)?;
+      return Ok((i, Pat::At(id, p.into())));
+    }
+  }
+  pat_hd(i, ts, no_struct)
+}
+
+fn pat_hd(i: usize, ts: &[Token], no_struct: bool) -> Result<(usize, Pat)> {
   if let Ok(i) = eat(i, ts, Token::Underscore) {
     return Ok((i, Pat::Wildcard));
   }
@@ -311,21 +523,108 @@ fn pat(i: usize, ts: &[Token]) -> Result<(usize, Pat)> {
     };
     return Ok((i, p));
   }
-  if let Ok((i, id)) = ident(i, ts) {
-    return match eat(i, ts, Token::LRound) {
+  if let Ok(i) = eat(i, ts, Token::LSquare) {
+    let (i, pats) = comma_sep(i, ts, pat)?;
+    let (i, rest) = match eat(i, ts, Token::DotDot) {
       Ok(i) => {
         let (i, p) = pat(i, ts)?;
+        (i, Some(p.into()))
+      }
+      Err(_) => (i, None),
+    };
+    let i = eat(i, ts, Token::RSquare)?;
+    return Ok((i, Pat::List(pats, rest)));
+  }
+  if let Ok((i, id, big)) = name(i, ts) {
+    if big {
+      let i = eat(i, ts, Token::LCurly)?;
+      return struct_pat_tl(i, ts, id);
+    }
+    if !no_struct {
+      if let Ok(i) = eat(i, ts, Token::LCurly) {
+        return struct_pat_tl(i, ts, id);
+      }
+    }
+    return match eat(i, ts, Token::LRound) {
+      Ok(i) => {
+        let (i, ps) = comma_sep(i, ts, pat)?;
         let i = eat(i, ts, Token::RRound)?;
-        Ok((i, Pat::Ctor(id, p.into())))
+        Ok((i, Pat::Ctor(id, ps)))
       }
+      // a qualified name must be a unit ctor. an unqualified one may also be, which is figured out
+      // during elaboration.
+      Err(_) if id.split().is_some() => Ok((i, Pat::Ctor(id, Vec::new()))),
       Err(_) => Ok((i, Pat::Ident(id))),
     };
   }
   err(i, ts, "a pattern")
 }
 
+fn struct_pat_tl(i: usize, ts: &[Token], id: Ident) -> Result<(usize, Pat)> {
+  let (i, fps) = comma_sep(i, ts, field_pat)?;
+  let (i, rest) = match eat(i, ts, Token::DotDot) {
+    Ok(i) => (i, true),
+    Err(_) => (i, false),
+  };
+  let i = eat(i, ts, Token::RCurly)?;
+  Ok((i, Pat::Struct(id, fps, rest)))
+}
+
 fn expr(i: usize, ts: &[Token]) -> Result<(usize, Expr)> {
-  let (mut i, mut e) = expr_hd(i, ts)?;
+  expr_prec(i, ts, 0, false)
+}
+
+/// Parses an expression which is followed by a block, like the head of a match. A ctor with named
+/// fields, like `circle { radius: 3 }`, must be in parentheses to be in such an expression, since
+/// otherwise the `{` would be ambiguous.
+fn expr_no_struct(i: usize, ts: &[Token]) -> Result<(usize, Expr)> {
+  expr_prec(i, ts, 0, true)
+}
+
+/// Parses an expression containing only binary operators with precedence at least `min_prec`.
+/// All the binary operators are left-associative.
+fn expr_prec(i: usize, ts: &[Token], min_prec: usize, no_struct: bool) -> Result<(usize, Expr)> {
+  let (mut i, mut e) = expr_unary(i, ts, no_struct)?;
+  while let Some(op) = ts.get(i).and_then(bin_op) {
+    if op.prec() < min_prec {
+      break;
+    }
+    let (j, rhs) = expr_prec(i + 1, ts, op.prec() + 1, no_struct)?;
+    e = Expr::BinOp(e.into(), op, rhs.into());
+    i = j;
+  }
+  Ok((i, e))
+}
+
+fn bin_op(t: &Token) -> Option<BinOp> {
+  let ret = match t {
+    Token::Plus => BinOp::Add,
+    Token::Minus => BinOp::Sub,
+    Token::Star => BinOp::Mul,
+    Token::Slash => BinOp::Div,
+    Token::EqualEqual => BinOp::Eq,
+    Token::BangEqual => BinOp::Neq,
+    Token::Lt => BinOp::Lt,
+    Token::Gt => BinOp::Gt,
+    Token::LtEqual => BinOp::Le,
+    Token::GtEqual => BinOp::Ge,
+    Token::AndAnd => BinOp::And,
+    Token::BarBar => BinOp::Or,
+    _ => return None,
+  };
+  Some(ret)
+}
+
+fn expr_unary(i: usize, ts: &[Token], no_struct: bool) -> Result<(usize, Expr)> {
+  if let Ok(i) = eat(i, ts, Token::Bang) {
+    let (i, e) = expr_unary(i, ts, no_struct)?;
+    return Ok((i, Expr::Not(e.into())));
+  }
+  expr_postfix(i, ts, no_struct)
+}
+
+fn expr_postfix(i: usize, ts: &[Token], no_struct: bool) -> Result<(usize, Expr)> {
+  let (mut i, mut e) = expr_hd(i, ts, no_struct)?;
   while let Ok(j) = eat(i, ts, Token::Dot) {
     let (j, id) = ident(j, ts)?;
     let (j, co) = call_opt(j, ts)?;
@@ -338,7 +637,7 @@ fn expr(i: usize, ts: &[Token]) -> Result<(usize, Expr)> {
   Ok((i, e))
 }
 
-fn expr_hd(i: usize, ts: &[Token]) -> Result<(usize, Expr)> {
+fn expr_hd(i: usize, ts: &[Token], no_struct: bool) -> Result<(usize, Expr)> {
   if let Ok((i, s)) = string(i, ts) {
     return Ok((i, Expr::String_(s)));
   }
@@ -355,40 +654,91 @@ fn expr_hd(i: usize, ts: &[Token]) -> Result<(usize, Expr)> {
     };
     return Ok((i, e));
   }
-  if let Ok((i, bi)) = big_ident(i, ts) {
-    let (i, args, _) = kinded_args_opt(i, ts)?;
-    let i = eat(i, ts, Token::LCurly)?;
-    let (i, fes) = comma_sep(i, ts, field_expr)?;
-    let i = eat(i, ts, Token::RCurly)?;
-    return Ok((i, Expr::Struct(bi, args, fes)));
-  }
-  if let Ok((i, id)) = ident(i, ts) {
-    let (i, co) = call_opt(i, ts)?;
-    return match co {
-      None => Ok((i, Expr::Ident(id))),
-      Some((args, es)) => Ok((i, Expr::FnCall(id, args, es))),
+  if let Ok(i) = eat(i, ts, Token::LSquare) {
+    let (i, exprs) = comma_sep(i, ts, expr)?;
+    let i = eat(i, ts, Token::RSquare)?;
+    return Ok((i, Expr::List(exprs)));
+  }
+  if let Ok((i, id, big)) = name(i, ts) {
+    let (j, args, _) = kinded_args_opt(i, ts)?;
+    let j = match eat(j, ts, Token::LCurly) {
+      Ok(j) if big || !no_struct => j,
+      Err(e) if big => return Err(e),
+      _ => {
+        let (i, co) = call_opt(i, ts)?;
+        return match co {
+          None => Ok((i, Expr::Ident(id))),
+          Some((args, es)) => Ok((i, Expr::FnCall(id, args, es))),
+        };
+      }
     };
+    let (i, fes) = comma_sep(j, ts, field_expr)?;
+    let (i, base) = match eat(i, ts, Token::DotDot) {
+      Ok(i) => {
+        let (i, e) = expr(i, ts)?;
+        (i, Some(e.into()))
+      }
+      Err(_) => (i, None),
+    };
+    let i = eat(i, ts, Token::RCurly)?;
+    return Ok((i, Expr::Struct(id, args, fes, base)));
   }
   if let Ok(i) = eat(i, ts, Token::Match) {
-    let (i, e) = expr(i, ts)?;
+    let (i, e) = expr_no_struct(i, ts)?;
     let i = eat(i, ts, Token::LCurly)?;
     let (i, arms) = arm_list(i, ts)?;
     let i = eat(i, ts, Token::RCurly)?;
     return Ok((i, Expr::Match(e.into(), arms)));
   }
+  if let Ok(i) = eat(i, ts, Token::If) {
+    return if_tl(i, ts);
+  }
+  if let Ok(i) = eat(i, ts, Token::Return) {
+    let (i, e) = expr_prec(i, ts, 0, no_struct)?;
+    return Ok((i, Expr::Return(e.into())));
+  }
   if let Ok((i, b)) = block(i, ts) {
     return Ok((i, Expr::Block(b.into())));
   }
   err(i, ts, "an expression")
 }
 
+/// Parses the rest of an if expression, after the `if`.
+fn if_tl(i: usize, ts: &[Token]) -> Result<(usize, Expr)> {
+  let (i, cond) = expr_no_struct(i, ts)?;
+  let (i, yes) = block(i, ts)?;
+  let (i, no) = match eat(i, ts, Token::Else) {
+    Ok(i) => match eat(i, ts, Token::If) {
+      Ok(i) => {
+        let (i, e) = if_tl(i, ts)?;
+        let b = Block {
+          stmts: vec![],
+          expr: Some(e),
+        };
+        (i, b)
+      }
+      Err(_) => block(i, ts)?,
+    },
+    Err(_) => {
+      let b = Block {
+        stmts: vec![],
+        expr: Some(Expr::Tuple(vec![])),
+      };
+      (i, b)
+    }
+  };
+  Ok((i, Expr::If(cond.into(), yes.into(), no.into())))
+}
+
 type Call = (Vec<Kinded>, Vec<Expr>);
 
 fn call_opt(i: usize, ts: &[Token]) -> Result<(usize, Option<Call>)> {
   let (i, args, got) = kinded_args_opt(i, ts)?;
   let i = match eat(i, ts, Token::LRound) {
     Ok(i) => i,
-    Err(e) => return if got { Err(e) } else { Ok((i, None)) },
+    // like `none[Nat]`, for a unit ctor.
+    Err(_) if got => return Ok((i, Some((args, Vec::new())))),
+    Err(_) => return Ok((i, None)),
   };
   let (i, es) = comma_sep(i, ts, expr)?;
   let i = eat(i, ts, Token::RRound)?;
@@ -406,11 +756,64 @@ fn field_expr(i: usize, ts: &[Token]) -> Result<(usize, Field<Expr>)> {
   }
 }
 
+/// Parses a possibly qualified name, like `x`, `Foo`, `Option::none`, or `geo::Point`. The bool is
+/// whether the last part is a big identifier.
+fn name(i: usize, ts: &[Token]) -> Result<(usize, Ident, bool)> {
+  let (mut i, mut ret, mut big) = name_part(i, ts)?;
+  while let Ok(j) = eat(i, ts, Token::ColonColon) {
+    let (j, part, b) = name_part(j, ts)?;
+    ret = Ident::qualified(&ret, &part);
+    big = b;
+    i = j;
+  }
+  Ok((i, ret, big))
+}
+
+/// Parses a name whose last part is big, like `geo::Point`.
+fn big_name(i: usize, ts: &[Token]) -> Result<(usize, Ident)> {
+  let (j, id, big) = name(i, ts)?;
+  if big {
+    Ok((j, id))
+  } else {
+    err(i, ts, "a big identifier")
+  }
+}
+
+fn name_part(i: usize, ts: &[Token]) -> Result<(usize, Ident, bool)> {
+  if let Ok((i, id)) = ident(i, ts) {
+    return Ok((i, id, false));
+  }
+  let (i, bi) = big_ident(i, ts)?;
+  Ok((i, bi, true))
+}
+
+fn field_pat(i: usize, ts: &[Token]) -> Result<(usize, Field<Pat>)> {
+  let (i, id) = ident(i, ts)?;
+  match eat(i, ts, Token::Colon) {
+    Ok(i) => {
+      let (i, p) = pat(i, ts)?;
+      Ok((i, Field::IdentAnd(id, p)))
+    }
+    Err(_) => Ok((i, Field::Ident(id))),
+  }
+}
+
 fn arm_list(mut i: usize, ts: &[Token]) -> Result<(usize, Vec<Arm>)> {
   let mut ret = Vec::new();
-  while let Ok((j, p)) = pat(i, ts) {
+  while let Ok((j, p)) = arm_pat(i, ts) {
+    let (j, guard) = match eat(j, ts, Token::If) {
+      Ok(j) => {
+        let (j, e) = expr_no_struct(j, ts)?;
+        (j, Some(e))
+      }
+      Err(_) => (j, None),
+    };
     let (j, b) = block(j, ts)?;
-    ret.push(Arm { pat: p, block: b });
+    ret.push(Arm {
+      pat: p,
+      guard,
+      block: b,
+    });
     i = j;
   }
   Ok((i, ret))
@@ -0,0 +1,506 @@
+//! Persistent collections, which share structure with the collections they were made from. Values
+//! are immutable, so a new version of a collection never needs to copy all of the old one.
+
+#[cfg(test)]
+mod tests;
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::rc::Rc;
+
+/// A persistent map, sorted by key. Inserting or removing copies only the path to the key in a
+/// balanced tree, and shares the rest.
+pub struct Map<K, V> {
+  root: Tree<K, V>,
+}
+
+type Tree<K, V> = Option<Rc<Node<K, V>>>;
+
+struct Node<K, V> {
+  key: K,
+  val: V,
+  left: Tree<K, V>,
+  right: Tree<K, V>,
+  height: usize,
+  len: usize,
+}
+
+impl<K, V> Map<K, V> {
+  /// Returns an empty map.
+  pub fn new() -> Self {
+    Self { root: None }
+  }
+
+  /// Returns the number of entries.
+  pub fn len(&self) -> usize {
+    len(&self.root)
+  }
+
+  /// Returns whether there are no entries.
+  pub fn is_empty(&self) -> bool {
+    self.root.is_none()
+  }
+
+  /// Returns the entries in order of their keys.
+  pub fn iter(&self) -> Iter<'_, K, V> {
+    let mut ret = Iter { stack: Vec::new() };
+    ret.push_left(&self.root);
+    ret
+  }
+}
+
+impl<K, V> Map<K, V>
+where
+  K: Ord + Clone,
+  V: Clone,
+{
+  /// Returns the value for the key, if any.
+  pub fn get(&self, key: &K) -> Option<&V> {
+    let mut t = &self.root;
+    while let Some(n) = t {
+      t = match key.cmp(&n.key) {
+        Ordering::Less => &n.left,
+        Ordering::Equal => return Some(&n.val),
+        Ordering::Greater => &n.right,
+      };
+    }
+    None
+  }
+
+  /// Returns whether there is a value for the key.
+  pub fn contains_key(&self, key: &K) -> bool {
+    self.get(key).is_some()
+  }
+
+  /// Returns this map with the value for the key set to `val`.
+  pub fn insert(&self, key: K, val: V) -> Self {
+    Self {
+      root: insert(&self.root, key, val),
+    }
+  }
+
+  /// Returns this map without the key.
+  pub fn remove(&self, key: &K) -> Self {
+    if !self.contains_key(key) {
+      return self.clone();
+    }
+    Self {
+      root: remove(&self.root, key),
+    }
+  }
+}
+
+fn len<K, V>(t: &Tree<K, V>) -> usize {
+  t.as_ref().map_or(0, |n| n.len)
+}
+
+fn height<K, V>(t: &Tree<K, V>) -> usize {
+  t.as_ref().map_or(0, |n| n.height)
+}
+
+fn node<K, V>(key: K, val: V, left: Tree<K, V>, right: Tree<K, V>) -> Tree<K, V> {
+  Some(Rc::new(Node {
+    height: 1 + height(&left).max(height(&right)),
+    len: 1 + len(&left) + len(&right),
+    key,
+    val,
+    left,
+    right,
+  }))
+}
+
+/// Like `node`, but rotates so the heights of the sides differ by at most one, given they differ by
+/// at most two.
+fn balance<K, V>(key: K, val: V, left: Tree<K, V>, right: Tree<K, V>) -> Tree<K, V>
+where
+  K: Clone,
+  V: Clone,
+{
+  let (hl, hr) = (height(&left), height(&right));
+  if hl > hr + 1 {
+    let l = left.unwrap();
+    if height(&l.left) >= height(&l.right) {
+      let right = node(key, val, l.right.clone(), right);
+      return node(l.key.clone(), l.val.clone(), l.left.clone(), right);
+    }
+    let lr = l.right.as_ref().unwrap();
+    let left = node(
+      l.key.clone(),
+      l.val.clone(),
+      l.left.clone(),
+      lr.left.clone(),
+    );
+    let right = node(key, val, lr.right.clone(), right);
+    return node(lr.key.clone(), lr.val.clone(), left, right);
+  }
+  if hr > hl + 1 {
+    let r = right.unwrap();
+    if height(&r.right) >= height(&r.left) {
+      let left = node(key, val, left, r.left.clone());
+      return node(r.key.clone(), r.val.clone(), left, r.right.clone());
+    }
+    let rl = r.left.as_ref().unwrap();
+    let left = node(key, val, left, rl.left.clone());
+    let right = node(
+      r.key.clone(),
+      r.val.clone(),
+      rl.right.clone(),
+      r.right.clone(),
+    );
+    return node(rl.key.clone(), rl.val.clone(), left, right);
+  }
+  node(key, val, left, right)
+}
+
+fn insert<K, V>(t: &Tree<K, V>, key: K, val: V) -> Tree<K, V>
+where
+  K: Ord + Clone,
+  V: Clone,
+{
+  let n = match t {
+    None => return node(key, val, None, None),
+    Some(n) => n,
+  };
+  match key.cmp(&n.key) {
+    Ordering::Less => balance(
+      n.key.clone(),
+      n.val.clone(),
+      insert(&n.left, key, val),
+      n.right.clone(),
+    ),
+    Ordering::Equal => node(key, val, n.left.clone(), n.right.clone()),
+    Ordering::Greater => balance(
+      n.key.clone(),
+      n.val.clone(),
+      n.left.clone(),
+      insert(&n.right, key, val),
+    ),
+  }
+}
+
+/// Requires the key be in the tree.
+fn remove<K, V>(t: &Tree<K, V>, key: &K) -> Tree<K, V>
+where
+  K: Ord + Clone,
+  V: Clone,
+{
+  let n = t.as_ref().unwrap();
+  match key.cmp(&n.key) {
+    Ordering::Less => balance(
+      n.key.clone(),
+      n.val.clone(),
+      remove(&n.left, key),
+      n.right.clone(),
+    ),
+    Ordering::Greater => balance(
+      n.key.clone(),
+      n.val.clone(),
+      n.left.clone(),
+      remove(&n.right, key),
+    ),
+    Ordering::Equal => match (&n.left, &n.right) {
+      (None, t) | (t, None) => t.clone(),
+      (left, right) => {
+        // replace the removed entry with the least entry on the right.
+        let mut min = right.as_ref().unwrap();
+        while let Some(l) = &min.left {
+          min = l;
+        }
+        let right = remove(right, &min.key);
+        balance(min.key.clone(), min.val.clone(), left.clone(), right)
+      }
+    },
+  }
+}
+
+/// An iterator over the entries of a map, in order of their keys.
+pub struct Iter<'a, K, V> {
+  /// The nodes whose entries and right subtrees are yet to be visited, innermost last.
+  stack: Vec<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+  fn push_left(&mut self, mut t: &'a Tree<K, V>) {
+    while let Some(n) = t {
+      self.stack.push(n);
+      t = &n.left;
+    }
+  }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+  type Item = (&'a K, &'a V);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let n = self.stack.pop()?;
+    self.push_left(&n.right);
+    Some((&n.key, &n.val))
+  }
+}
+
+impl<K, V> Clone for Map<K, V> {
+  fn clone(&self) -> Self {
+    Self {
+      root: self.root.clone(),
+    }
+  }
+}
+
+impl<K, V> Default for Map<K, V> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for Map<K, V> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<K: Eq, V: Eq> Eq for Map<K, V> {}
+
+impl<K: PartialOrd, V: PartialOrd> PartialOrd for Map<K, V> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    self.iter().partial_cmp(other.iter())
+  }
+}
+
+impl<K: Ord, V: Ord> Ord for Map<K, V> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.iter().cmp(other.iter())
+  }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Map<K, V> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+/// A persistent set, sorted. Like a map with no values.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct Set<T>(Map<T, ()>);
+
+impl<T> Clone for Set<T> {
+  fn clone(&self) -> Self {
+    Self(self.0.clone())
+  }
+}
+
+impl<T> Default for Set<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T> Set<T> {
+  /// Returns an empty set.
+  pub fn new() -> Self {
+    Self(Map::new())
+  }
+
+  /// Returns the number of values.
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  /// Returns whether there are no values.
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Returns the values in order.
+  pub fn iter(&self) -> impl Iterator<Item = &T> {
+    self.0.iter().map(|(x, _)| x)
+  }
+}
+
+impl<T: Ord + Clone> Set<T> {
+  /// Returns whether the value is in the set.
+  pub fn contains(&self, x: &T) -> bool {
+    self.0.contains_key(x)
+  }
+
+  /// Returns this set with the value.
+  pub fn insert(&self, x: T) -> Self {
+    Self(self.0.insert(x, ()))
+  }
+
+  /// Returns this set without the value.
+  pub fn remove(&self, x: &T) -> Self {
+    Self(self.0.remove(x))
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Set<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.iter()).finish()
+  }
+}
+
+/// A persistent list of cons cells. Adding to or removing from the front shares the rest of the
+/// list.
+pub struct List<T> {
+  head: Cells<T>,
+}
+
+type Cells<T> = Option<Rc<Cell<T>>>;
+
+struct Cell<T> {
+  val: T,
+  next: Cells<T>,
+  /// The length of the list starting at this cell.
+  len: usize,
+}
+
+impl<T> List<T> {
+  /// Returns an empty list.
+  pub fn new() -> Self {
+    Self { head: None }
+  }
+
+  /// Returns the number of values.
+  pub fn len(&self) -> usize {
+    self.head.as_ref().map_or(0, |c| c.len)
+  }
+
+  /// Returns whether there are no values.
+  pub fn is_empty(&self) -> bool {
+    self.head.is_none()
+  }
+
+  /// Returns this list with `x` added to the front.
+  pub fn cons(&self, x: T) -> Self {
+    Self {
+      head: Some(Rc::new(Cell {
+        len: self.len() + 1,
+        val: x,
+        next: self.head.clone(),
+      })),
+    }
+  }
+
+  /// Returns the first value and the rest of the list, if not empty.
+  pub fn split_first(&self) -> Option<(&T, Self)> {
+    let c = self.head.as_ref()?;
+    Some((
+      &c.val,
+      Self {
+        head: c.next.clone(),
+      },
+    ))
+  }
+
+  /// Returns the list without the first `n` values, which must be at most the length.
+  pub fn skip(&self, n: usize) -> Self {
+    let mut ret = self.clone();
+    for _ in 0..n {
+      ret = ret.split_first().unwrap().1;
+    }
+    ret
+  }
+
+  /// Returns the value at index `n`, if any.
+  pub fn nth(&self, n: usize) -> Option<&T> {
+    self.iter().nth(n)
+  }
+
+  /// Returns the values in order.
+  pub fn iter(&self) -> ListIter<'_, T> {
+    ListIter {
+      cell: self.head.as_deref(),
+    }
+  }
+}
+
+impl<T: Clone> List<T> {
+  /// Returns this list followed by `other`, which is shared.
+  pub fn append(&self, other: &Self) -> Self {
+    let xs: Vec<_> = self.iter().collect();
+    xs.into_iter()
+      .rev()
+      .fold(other.clone(), |acc, x| acc.cons(x.clone()))
+  }
+
+  /// Returns this list backwards.
+  pub fn reverse(&self) -> Self {
+    self.iter().fold(Self::new(), |acc, x| acc.cons(x.clone()))
+  }
+}
+
+/// An iterator over the values of a list, in order.
+pub struct ListIter<'a, T> {
+  cell: Option<&'a Cell<T>>,
+}
+
+impl<'a, T> Iterator for ListIter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let c = self.cell?;
+    self.cell = c.next.as_deref();
+    Some(&c.val)
+  }
+}
+
+impl<T> FromIterator<T> for List<T> {
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let xs: Vec<_> = iter.into_iter().collect();
+    xs.into_iter().rev().fold(Self::new(), |acc, x| acc.cons(x))
+  }
+}
+
+impl<T> Drop for List<T> {
+  /// Drops the cells no other list shares one at a time, rather than recursively, so long lists
+  /// don't overflow the stack.
+  fn drop(&mut self) {
+    let mut cur = self.head.take();
+    while let Some(c) = cur {
+      cur = match Rc::try_unwrap(c) {
+        Ok(mut c) => c.next.take(),
+        Err(_) => break,
+      };
+    }
+  }
+}
+
+impl<T> Clone for List<T> {
+  fn clone(&self) -> Self {
+    Self {
+      head: self.head.clone(),
+    }
+  }
+}
+
+impl<T> Default for List<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+impl<T: PartialOrd> PartialOrd for List<T> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    self.iter().partial_cmp(other.iter())
+  }
+}
+
+impl<T: Ord> Ord for List<T> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.iter().cmp(other.iter())
+  }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
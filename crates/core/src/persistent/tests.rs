@@ -0,0 +1,94 @@
+use super::{List, Map, Node, Set, Tree};
+use std::collections::BTreeMap;
+
+/// Returns the height of the tree, checking that it is balanced and sorted and that the heights and
+/// lengths are right.
+fn ck_tree(t: &Tree<u64, u64>) -> usize {
+  let n: &Node<u64, u64> = match t {
+    None => return 0,
+    Some(n) => n,
+  };
+  let hl = ck_tree(&n.left);
+  let hr = ck_tree(&n.right);
+  assert!(hl.abs_diff(hr) <= 1);
+  assert_eq!(n.height, 1 + hl.max(hr));
+  assert_eq!(n.len, 1 + super::len(&n.left) + super::len(&n.right));
+  if let Some(l) = &n.left {
+    assert!(l.key < n.key);
+  }
+  if let Some(r) = &n.right {
+    assert!(r.key > n.key);
+  }
+  n.height
+}
+
+#[test]
+fn like_btree_map() {
+  let mut m = Map::new();
+  let mut want = BTreeMap::new();
+  // a fixed but scrambled sequence of keys, with repeats.
+  let mut k = 7u64;
+  for i in 0..500 {
+    k = (k * 31 + 17) % 101;
+    if i % 3 == 0 {
+      m = m.remove(&k);
+      want.remove(&k);
+    } else {
+      m = m.insert(k, i);
+      want.insert(k, i);
+    }
+    ck_tree(&m.root);
+    assert_eq!(m.len(), want.len());
+    assert!(m.iter().eq(want.iter()));
+  }
+  for k in 0..101 {
+    assert_eq!(m.get(&k), want.get(&k));
+  }
+}
+
+#[test]
+fn old_versions_unchanged() {
+  let a = Map::new().insert(1, "a").insert(2, "b");
+  let b = a.insert(3, "c").remove(&1);
+  assert_eq!(a.iter().collect::<Vec<_>>(), [(&1, &"a"), (&2, &"b")]);
+  assert_eq!(b.iter().collect::<Vec<_>>(), [(&2, &"b"), (&3, &"c")]);
+}
+
+#[test]
+fn set() {
+  let s = Set::new().insert(3).insert(1).insert(3).insert(2);
+  assert_eq!(s.len(), 3);
+  assert!(s.contains(&2));
+  assert!(!s.remove(&2).contains(&2));
+  assert_eq!(s.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+  assert!(Set::new().insert(1) < Set::new().insert(2));
+}
+
+#[test]
+fn list() {
+  let xs: List<u64> = [1, 2, 3].into_iter().collect();
+  let ys = xs.cons(0);
+  assert_eq!(ys.len(), 4);
+  assert_eq!(ys.skip(1), xs);
+  assert_eq!(xs.nth(2), Some(&3));
+  assert_eq!(xs.nth(3), None);
+  let (x, rest) = xs.split_first().unwrap();
+  assert_eq!((*x, rest.len()), (1, 2));
+  let zs = xs.append(&ys);
+  assert_eq!(
+    zs.iter().copied().collect::<Vec<_>>(),
+    [1, 2, 3, 0, 1, 2, 3]
+  );
+  assert_eq!(
+    zs.reverse().iter().copied().collect::<Vec<_>>(),
+    [3, 2, 1, 0, 3, 2, 1]
+  );
+  assert!(List::new().cons(1) < List::new().cons(2));
+}
+
+#[test]
+fn long_list_drop() {
+  let xs: List<u64> = (0..1_000_000).collect();
+  assert_eq!(xs.len(), 1_000_000);
+  drop(xs);
+}
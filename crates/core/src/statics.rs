@@ -1,20 +1,57 @@
 //! Static verification.
 
-use crate::cst::{Arm, Block, Expr, Field, Kind, Kinded, Param, Pat, Stmt, TopDefn};
+use crate::cst::{
+  Arm, Block, Expr, Field, FnDefn, Kind, Kinded, Param, Pat, Payload, Stmt, TopDefn, TypeDefn,
+};
 use crate::error::{Error, Result};
 use crate::ident::Ident;
 use crate::std_lib as birb_std_lib;
+use crate::warning::Warning;
 use std::collections::{HashMap, HashSet};
 
-/// Checks whether the sequence of top-level definitions is statically well-formed.
-pub fn get(top_defns: &[TopDefn]) -> Result<()> {
+/// What was found out about well-formed definitions.
+#[derive(Debug)]
+pub struct Info {
+  /// Warnings about definitions which are well-formed but likely mistakes.
+  pub warnings: Vec<Warning>,
+  /// The effects inferred for the fns which are not `pub` and have no `affects`, in order.
+  pub effects: Vec<(Ident, Kinded)>,
+}
+
+/// Checks whether the sequence of top-level definitions is statically well-formed. The bodies of
+/// the definitions in the `checked` packages are assumed to be.
+pub fn get(top_defns: &[TopDefn], checked: &HashSet<Ident>) -> Result<Info> {
   let mut cx = Cx::default();
   let mut var_cx = VarCx::default();
   cx.effects = birb_std_lib::effects();
-  for td in top_defns {
-    ck_top_defn(&mut cx, &mut var_cx, td)?;
-    assert!(var_cx.big_vars.is_empty());
-    assert!(var_cx.vars.is_empty());
+  // the data types, then the signatures, then the bodies, so that definitions may refer to ones
+  // after them.
+  ck_data_types(&mut cx, &mut var_cx, top_defns)?;
+  assert_clean(&var_cx);
+  let warnings = ck_inhabited(&cx, top_defns);
+  // the traits come before the other signatures, since impls and bounds refer to them.
+  let (traits, others): (Vec<_>, Vec<_>) = top_defns
+    .iter()
+    .partition(|td| matches!(td, TopDefn::Trait(..)));
+  for td in traits.into_iter().chain(others) {
+    ck_sig(&mut cx, &mut var_cx, td)?;
+    assert_clean(&var_cx);
+  }
+  // the bodies of the checked packages can't affect the other packages, since only pub fns may be
+  // used from other packages and they must give their effects.
+  let top_defns_to_ck: Vec<_> = top_defns
+    .iter()
+    .filter(|td| match td {
+      TopDefn::Fn_(fn_) => !in_packages(checked, &fn_.name),
+      TopDefn::Impl(impl_) => !impl_.fns.iter().any(|f| in_packages(checked, &f.name)),
+      _ => true,
+    })
+    .collect();
+  let effects = infer_effects(&mut cx, &mut var_cx, &top_defns_to_ck)?;
+  assert_clean(&var_cx);
+  for td in top_defns_to_ck {
+    ck_body(&cx, &mut var_cx, td)?;
+    assert_clean(&var_cx);
   }
   let main = top_defns.iter().find_map(|td| match td {
     TopDefn::Fn_(info) => {
@@ -30,50 +67,110 @@ pub fn get(top_defns: &[TopDefn]) -> Result<()> {
     None => return Err(Error::NoMain),
     Some(x) => x,
   };
-  if !main.big_params.is_empty()
-    || !main.params.is_empty()
-    || main.requires.is_some()
-    || main.ensures.is_some()
-  {
+  // main may take the command-line arguments as one List[Str] param, or as any number of Str params.
+  let str_list = Kinded::Ident(Ident::new(birb_std_lib::LIST), vec![str_type()]);
+  let args_ok = match &main.params[..] {
+    [p] if p.type_ == str_list => true,
+    ps => ps.iter().all(|p| p.type_ == str_type()),
+  };
+  if !main.big_params.is_empty() || !args_ok || main.requires.is_some() || main.ensures.is_some() {
     return Err(Error::InvalidMain);
   }
-  Ok(())
+  Ok(Info { warnings, effects })
 }
 
 #[derive(Default)]
 struct Cx {
   structs: HashMap<Ident, StructInfo>,
   enums: HashMap<Ident, EnumInfo>,
+  types: HashMap<Ident, TypeInfo>,
+  aliases: HashMap<Ident, AliasInfo>,
   fns: HashMap<Ident, FnInfo>,
   effects: HashSet<Ident>,
+  traits: HashMap<Ident, TraitInfo>,
+  /// The impls, by trait and type name.
+  impls: HashMap<(Ident, Ident), ImplInfo>,
 }
 
 #[derive(Debug, Default, Clone)]
 struct VarCx {
   big_vars: HashMap<Ident, Kind>,
+  /// The big vars which may be used as Map or Set keys.
+  comparable: HashSet<Ident>,
+  /// The traits which the big vars implement, as pairs of big var and trait.
+  bounds: HashSet<(Ident, Ident)>,
   vars: HashMap<Ident, Kinded>,
+  /// The return type of the enclosing function body, if in one.
+  ret_type: Option<Kinded>,
+  /// The module of the definition being checked. None for the main file.
+  module: Option<Ident>,
 }
 
 #[derive(Clone)]
 struct StructInfo {
+  pub_: bool,
   params: Vec<Param<Ident, Kind>>,
   fields: HashMap<Ident, Kinded>,
 }
 
 struct EnumInfo {
+  pub_: bool,
   params: Vec<Param<Ident, Kind>>,
-  ctors: HashMap<Ident, Kinded>,
+  ctors: HashMap<Ident, CtorInfo>,
+}
+
+enum CtorInfo {
+  Positional(Vec<Kinded>),
+  Named(HashMap<Ident, Kinded>),
+}
+
+impl CtorInfo {
+  fn types(&self) -> Vec<&Kinded> {
+    match self {
+      CtorInfo::Positional(ts) => ts.iter().collect(),
+      CtorInfo::Named(fields) => fields.values().collect(),
+    }
+  }
+}
+
+struct TypeInfo {
+  pub_: bool,
+  params: Vec<Param<Ident, Kind>>,
+}
+
+struct AliasInfo {
+  pub_: bool,
+  params: Vec<Param<Ident, Kind>>,
+  /// The definition, with all aliases in it already expanded.
+  def: Kinded,
+  kind: Kind,
 }
 
 #[derive(Clone)]
 struct FnInfo {
+  pub_: bool,
   big_params: Vec<Param<Ident, Kind>>,
+  bounds: Vec<Param<Ident, Ident>>,
   params: Vec<Param<Ident, Kinded>>,
   ret_type: Kinded,
 }
 
+struct TraitInfo {
+  pub_: bool,
+  /// The fns, with `Self` for the type implementing the trait.
+  fns: HashMap<Ident, FnInfo>,
+}
+
+struct ImplInfo {
+  params: Vec<Param<Ident, Kind>>,
+  bounds: Vec<Param<Ident, Ident>>,
+}
+
 fn ck_ident(cx: &Cx, id: &Ident) -> Result<()> {
-  if cx.fns.contains_key(id) || cx.enums.iter().any(|(_, info)| info.ctors.contains_key(id)) {
+  if cx.fns.contains_key(id)
+    || get_trait_fn(cx, id).is_some()
+    || cx.enums.iter().any(|(_, info)| info.ctors.contains_key(id))
+  {
     Err(Error::DuplicateIdentifier(id.clone()))
   } else {
     Ok(())
@@ -81,160 +178,798 @@ fn ck_ident(cx: &Cx, id: &Ident) -> Result<()> {
 }
 
 fn ck_big_ident(cx: &Cx, bi: &Ident) -> Result<()> {
-  if cx.structs.contains_key(bi) || cx.enums.contains_key(bi) || cx.effects.contains(bi) {
+  if cx.structs.contains_key(bi)
+    || cx.enums.contains_key(bi)
+    || cx.types.contains_key(bi)
+    || cx.aliases.contains_key(bi)
+    || cx.effects.contains(bi)
+    || cx.traits.contains_key(bi)
+  {
     Err(Error::DuplicateIdentifier(bi.clone()))
   } else {
     Ok(())
   }
 }
 
-fn ck_top_defn(cx: &mut Cx, var_cx: &mut VarCx, td: &TopDefn) -> Result<()> {
-  match td {
-    TopDefn::Struct(struct_) => {
-      ck_big_ident(&cx, &struct_.name)?;
-      for p in struct_.params.iter() {
-        if var_cx
-          .big_vars
-          .insert(p.ident.clone(), p.type_.clone())
-          .is_some()
-        {
-          return Err(Error::DuplicateIdentifier(p.ident.clone()));
+/// Checks the fields of a struct, or of a ctor with named fields.
+fn ck_fields(
+  cx: &Cx,
+  var_cx: &VarCx,
+  name: &Ident,
+  fields: &[Param<Ident, Kinded>],
+) -> Result<HashMap<Ident, Kinded>> {
+  let mut ret = HashMap::with_capacity(fields.len());
+  for p in fields {
+    ck_has_kind(cx, var_cx, &p.type_, Kind::Type)?;
+    if ret.insert(p.ident.clone(), p.type_.clone()).is_some() {
+      return Err(Error::DuplicateField(name.clone(), p.ident.clone()));
+    }
+  }
+  Ok(ret)
+}
+
+fn assert_clean(var_cx: &VarCx) {
+  assert!(var_cx.big_vars.is_empty());
+  assert!(var_cx.comparable.is_empty());
+  assert!(var_cx.bounds.is_empty());
+  assert!(var_cx.vars.is_empty());
+}
+
+/// The uses of the params of a struct or enum, for inferring their kinds.
+struct DataUses<'a> {
+  name: &'a Ident,
+  params: &'a [Param<Ident, Option<Kind>>],
+  uses: Vec<(&'a Kinded, Option<Kind>)>,
+}
+
+/// Checks the structs, enums and types. The kinds of the structs and enums are found before any of
+/// their fields are checked, so they may refer to each other regardless of order.
+fn ck_data_types(cx: &mut Cx, var_cx: &mut VarCx, top_defns: &[TopDefn]) -> Result<()> {
+  let mut seen = HashSet::new();
+  let mut datas = Vec::new();
+  for td in top_defns {
+    match td {
+      TopDefn::Struct(struct_) => {
+        ck_big_ident(cx, &struct_.name)?;
+        if !seen.insert(&struct_.name) {
+          return Err(Error::DuplicateIdentifier(struct_.name.clone()));
         }
+        let uses = struct_
+          .fields
+          .iter()
+          .map(|f| (&f.type_, Some(Kind::Type)))
+          .collect();
+        datas.push(DataUses {
+          name: &struct_.name,
+          params: &struct_.params,
+          uses,
+        });
       }
-      let mut fields = HashMap::with_capacity(struct_.fields.len());
-      for p in struct_.fields.iter() {
-        ck_has_kind(&cx, &var_cx, &p.type_, Kind::Type)?;
-        if fields.insert(p.ident.clone(), p.type_.clone()).is_some() {
-          return Err(Error::DuplicateField(struct_.name.clone(), p.ident.clone()));
+      TopDefn::Enum(enum_) => {
+        ck_big_ident(cx, &enum_.name)?;
+        if !seen.insert(&enum_.name) {
+          return Err(Error::DuplicateIdentifier(enum_.name.clone()));
         }
+        let uses = enum_
+          .ctors
+          .iter()
+          .flat_map(|p| match &p.type_ {
+            Payload::Positional(ts) => ts.iter().collect::<Vec<_>>(),
+            Payload::Named(fields) => fields.iter().map(|f| &f.type_).collect(),
+          })
+          .map(|t| (t, Some(Kind::Type)))
+          .collect();
+        datas.push(DataUses {
+          name: &enum_.name,
+          params: &enum_.params,
+          uses,
+        });
       }
-      for p in struct_.params.iter() {
-        var_cx.big_vars.remove(&p.ident).unwrap();
+      TopDefn::Type(type_) if type_.def.is_none() => {
+        ck_big_ident(cx, &type_.name)?;
+        if seen.contains(&type_.name) {
+          return Err(Error::DuplicateIdentifier(type_.name.clone()));
+        }
+        if !is_intrinsic(&birb_std_lib::INTRINSIC_TYPES, &type_.name) {
+          return Err(Error::MissingBody(type_.name.clone()));
+        }
+        var_cx.module = module_of(&type_.name);
+        cx.types.insert(
+          type_.name.clone(),
+          TypeInfo {
+            pub_: type_.pub_,
+            params: infer_kinds(cx, var_cx, &type_.params, &[])?,
+          },
+        );
       }
-      cx.structs.insert(
-        struct_.name.clone(),
-        StructInfo {
-          params: struct_.params.clone(),
-          fields,
-        },
-      );
+      _ => {}
     }
-    TopDefn::Enum(enum_) => {
-      ck_big_ident(&cx, &enum_.name)?;
-      for p in enum_.params.iter() {
-        if var_cx
-          .big_vars
-          .insert(p.ident.clone(), p.type_.clone())
-          .is_some()
-        {
-          return Err(Error::DuplicateIdentifier(p.ident.clone()));
+  }
+  let mut params = infer_data_kinds(cx, var_cx, &datas)?;
+  for td in top_defns {
+    match td {
+      TopDefn::Struct(struct_) => {
+        let info = StructInfo {
+          pub_: struct_.pub_,
+          params: params.remove(&struct_.name).unwrap(),
+          fields: HashMap::new(),
+        };
+        cx.structs.insert(struct_.name.clone(), info);
+      }
+      TopDefn::Enum(enum_) => {
+        let info = EnumInfo {
+          pub_: enum_.pub_,
+          params: params.remove(&enum_.name).unwrap(),
+          ctors: HashMap::new(),
+        };
+        cx.enums.insert(enum_.name.clone(), info);
+      }
+      _ => {}
+    }
+  }
+  // the aliases are expanded when defined, so they come after the kinds of the data types are
+  // known but before the fields are checked.
+  let aliases: Vec<_> = top_defns
+    .iter()
+    .filter_map(|td| match td {
+      TopDefn::Type(type_) if type_.def.is_some() => Some(type_),
+      _ => None,
+    })
+    .collect();
+  for type_ in aliases.iter() {
+    ck_alias(cx, var_cx, &aliases, &mut Vec::new(), type_)?;
+  }
+  for td in top_defns {
+    match td {
+      TopDefn::Struct(struct_) => {
+        var_cx.module = module_of(&struct_.name);
+        let params = cx.structs[&struct_.name].params.clone();
+        add_big_vars(var_cx, &params)?;
+        let fields = ck_fields(cx, var_cx, &struct_.name, &struct_.fields)?;
+        rm_big_vars(var_cx, &params);
+        cx.structs.get_mut(&struct_.name).unwrap().fields = fields;
+      }
+      TopDefn::Enum(enum_) => {
+        var_cx.module = module_of(&enum_.name);
+        let params = cx.enums[&enum_.name].params.clone();
+        add_big_vars(var_cx, &params)?;
+        let mut ctors = HashMap::with_capacity(enum_.ctors.len());
+        for p in enum_.ctors.iter() {
+          let info = match &p.type_ {
+            Payload::Positional(ts) => {
+              for t in ts {
+                ck_has_kind(cx, var_cx, t, Kind::Type)?;
+              }
+              CtorInfo::Positional(ts.clone())
+            }
+            Payload::Named(fields) => CtorInfo::Named(ck_fields(cx, var_cx, &p.ident, fields)?),
+          };
+          if ctors.insert(p.ident.clone(), info).is_some() {
+            return Err(Error::DuplicateIdentifier(p.ident.clone()));
+          }
         }
+        rm_big_vars(var_cx, &params);
+        cx.enums.get_mut(&enum_.name).unwrap().ctors = ctors;
       }
-      let mut ctors = HashMap::with_capacity(enum_.ctors.len());
-      for p in enum_.ctors.iter() {
-        ck_ident(&cx, &p.ident)?;
-        ck_has_kind(&cx, &var_cx, &p.type_, Kind::Type)?;
-        ctors.insert(p.ident.clone(), p.type_.clone());
+      _ => {}
+    }
+  }
+  Ok(())
+}
+
+/// Checks and registers the alias, after the aliases it refers to. `visiting` is the aliases whose
+/// checks are in progress, which refer to this one.
+fn ck_alias(
+  cx: &mut Cx,
+  var_cx: &mut VarCx,
+  aliases: &[&TypeDefn],
+  visiting: &mut Vec<Ident>,
+  type_: &TypeDefn,
+) -> Result<()> {
+  if cx.aliases.contains_key(&type_.name) {
+    return Ok(());
+  }
+  if let Some(idx) = visiting.iter().position(|x| *x == type_.name) {
+    let mut cycle = visiting[idx..].to_vec();
+    cycle.push(type_.name.clone());
+    return Err(Error::CyclicAlias(cycle));
+  }
+  // a param with the name of a global type would be ambiguous in the definition. the other aliases
+  // may not be checked yet, so they are found by name.
+  for p in type_.params.iter() {
+    if aliases.iter().any(|other| other.name == p.ident) {
+      return Err(Error::DuplicateIdentifier(p.ident.clone()));
+    }
+    ck_big_ident(cx, &p.ident)?;
+  }
+  let def = type_.def.as_ref().unwrap();
+  visiting.push(type_.name.clone());
+  for other in aliases.iter() {
+    if mentions(def, &other.name) {
+      ck_alias(cx, var_cx, aliases, visiting, other)?;
+    }
+  }
+  visiting.pop();
+  ck_big_ident(cx, &type_.name)?;
+  var_cx.module = module_of(&type_.name);
+  let params = infer_kinds(cx, var_cx, &type_.params, &[(def, None)])?;
+  add_big_vars(var_cx, &params)?;
+  let kind = get_kind(cx, var_cx, def)?;
+  rm_big_vars(var_cx, &params);
+  cx.aliases.insert(
+    type_.name.clone(),
+    AliasInfo {
+      pub_: type_.pub_,
+      params,
+      def: expand(cx, def),
+      kind,
+    },
+  );
+  Ok(())
+}
+
+/// Returns warnings for the structs and enums with no finite values, like `struct A { a: A }`, or
+/// an enum whose every ctor has such a field. The params are assumed to have values. Enums with no
+/// ctors are meant to have no values, so there is no warning for them.
+fn ck_inhabited(cx: &Cx, top_defns: &[TopDefn]) -> Vec<Warning> {
+  let mut inhabited = HashSet::new();
+  loop {
+    let mut changed = false;
+    for (name, info) in cx.structs.iter() {
+      if !inhabited.contains(name)
+        && info
+          .fields
+          .values()
+          .all(|t| is_inhabited(cx, &inhabited, &mut Vec::new(), t))
+      {
+        inhabited.insert(name.clone());
+        changed = true;
+      }
+    }
+    for (name, info) in cx.enums.iter() {
+      let any_ctor = info.ctors.values().any(|ctor| {
+        ctor
+          .types()
+          .into_iter()
+          .all(|t| is_inhabited(cx, &inhabited, &mut Vec::new(), t))
+      });
+      if !inhabited.contains(name) && any_ctor {
+        inhabited.insert(name.clone());
+        changed = true;
       }
-      for p in enum_.params.iter() {
-        var_cx.big_vars.remove(&p.ident).unwrap();
+    }
+    if !changed {
+      break;
+    }
+  }
+  top_defns
+    .iter()
+    .filter_map(|td| match td {
+      TopDefn::Struct(struct_) if !inhabited.contains(&struct_.name) => {
+        Some(Warning::Uninhabited(struct_.name.clone()))
       }
-      cx.enums.insert(
-        enum_.name.clone(),
-        EnumInfo {
-          params: enum_.params.clone(),
-          ctors,
-        },
-      );
+      TopDefn::Enum(enum_) if !enum_.ctors.is_empty() && !inhabited.contains(&enum_.name) => {
+        Some(Warning::Uninhabited(enum_.name.clone()))
+      }
+      _ => None,
+    })
+    .collect()
+}
+
+/// Returns whether the type has values, given the structs and enums known to have values for some
+/// args. A struct or enum given args has values if its fields do with the args substituted in.
+/// `visiting` is the structs and enums whose args are being substituted in, which are not
+/// substituted in again, so that this stops.
+fn is_inhabited(
+  cx: &Cx,
+  inhabited: &HashSet<Ident>,
+  visiting: &mut Vec<Ident>,
+  typ: &Kinded,
+) -> bool {
+  match expand(cx, typ) {
+    Kinded::Ident(name, args) => {
+      if !inhabited.contains(&name) {
+        return !(cx.structs.contains_key(&name) || cx.enums.contains_key(&name));
+      }
+      if args.is_empty() || visiting.contains(&name) {
+        return true;
+      }
+      let (params, field_types): (_, Vec<Vec<&Kinded>>) = if let Some(info) = cx.structs.get(&name)
+      {
+        (&info.params, vec![info.fields.values().collect()])
+      } else if let Some(info) = cx.enums.get(&name) {
+        (
+          &info.params,
+          info.ctors.values().map(CtorInfo::types).collect(),
+        )
+      } else {
+        return true;
+      };
+      let big_vars: HashMap<_, _> = params
+        .iter()
+        .zip(args)
+        .map(|(p, a)| (p.ident.clone(), a))
+        .collect();
+      visiting.push(name);
+      // a struct has one list of fields, and an enum has one for each ctor.
+      let ret = field_types.into_iter().any(|ts| {
+        ts.into_iter().all(|t| {
+          let t = subst_kinded(&big_vars, t.clone());
+          is_inhabited(cx, inhabited, visiting, &t)
+        })
+      });
+      visiting.pop();
+      ret
     }
+    Kinded::Tuple(ts) => ts.iter().all(|t| is_inhabited(cx, inhabited, visiting, t)),
+    Kinded::Never => false,
+    // there are always fns, even from or to types with no values.
+    Kinded::Arrow(..) | Kinded::Effectful(..) | Kinded::Set(..) => true,
+  }
+}
+
+/// Checks and registers the signatures of the traits, impls and fns.
+fn ck_sig(cx: &mut Cx, var_cx: &mut VarCx, td: &TopDefn) -> Result<()> {
+  match td {
+    TopDefn::Struct(..) | TopDefn::Enum(..) | TopDefn::Type(..) => {}
+    TopDefn::Import(..) => unreachable!("import"),
     TopDefn::Fn_(fn_) => {
-      ck_ident(&cx, &fn_.name)?;
-      for p in fn_.big_params.iter() {
-        if var_cx
-          .big_vars
-          .insert(p.ident.clone(), p.type_.clone())
-          .is_some()
-        {
-          return Err(Error::DuplicateIdentifier(p.ident.clone()));
+      ck_ident(cx, &fn_.name)?;
+      let info = ck_fn_sig(cx, var_cx, fn_)?;
+      cx.fns.insert(fn_.name.clone(), info);
+    }
+    TopDefn::Trait(trait_) => {
+      ck_big_ident(cx, &trait_.name)?;
+      var_cx.module = module_of(&trait_.name);
+      var_cx.big_vars.insert(self_ident(), Kind::Type);
+      let mut fns = HashMap::with_capacity(trait_.fns.len());
+      for f in trait_.fns.iter() {
+        ck_ident(cx, &f.name)?;
+        let valid = f.big_params.is_empty()
+          && f.requires.is_none()
+          && f.ensures.is_none()
+          && f.body.is_none()
+          && f.params.first().is_some_and(|p| p.type_ == self_type());
+        if !valid {
+          return Err(Error::InvalidTraitFn(f.name.clone()));
         }
-      }
-      for p in fn_.params.iter() {
-        ck_has_kind(&cx, &var_cx, &p.type_, Kind::Type)?;
-        if var_cx
-          .vars
-          .insert(p.ident.clone(), p.type_.clone())
-          .is_some()
-        {
-          return Err(Error::DuplicateIdentifier(p.ident.clone()));
+        for p in f.params.iter() {
+          ck_has_kind(cx, var_cx, &p.type_, Kind::Type)?;
+        }
+        ck_has_kind(cx, var_cx, &f.ret_type, Kind::Type)?;
+        let info = FnInfo {
+          pub_: trait_.pub_,
+          big_params: Vec::new(),
+          bounds: Vec::new(),
+          params: f.params.clone(),
+          ret_type: f.ret_type.clone(),
+        };
+        if fns.insert(f.name.clone(), info).is_some() {
+          return Err(Error::DuplicateIdentifier(f.name.clone()));
         }
       }
-      ck_has_kind(&cx, &var_cx, &fn_.ret_type, Kind::Type)?;
-      if let Some(req) = &fn_.requires {
-        let got = get_expr_type(&cx, &var_cx, req)?;
-        if got.typ != bool_type() {
-          return Err(Error::MismatchedTypes(bool_type(), got.typ));
+      var_cx.big_vars.remove(&self_ident()).unwrap();
+      cx.traits.insert(
+        trait_.name.clone(),
+        TraitInfo {
+          pub_: trait_.pub_,
+          fns,
+        },
+      );
+    }
+    TopDefn::Impl(impl_) => {
+      // the fns of the impl are in the module of the impl.
+      var_cx.module = impl_.fns.first().and_then(|f| module_of(&f.name));
+      let trait_fns = match cx.traits.get(&impl_.trait_) {
+        Some(info) => {
+          ck_visible(var_cx, &impl_.trait_, info.pub_)?;
+          info.fns.clone()
         }
-        if let Some(e) = got.effects.into_iter().next() {
-          return Err(Error::InvalidEffectUse(fn_.name.clone(), e));
+        None => return Err(Error::UndefinedIdentifier(impl_.trait_.clone())),
+      };
+      let type_name = match &impl_.type_ {
+        Kinded::Ident(name, args)
+          if !cx.aliases.contains_key(name)
+            && args.len() == impl_.big_params.len()
+            && args
+              .iter()
+              .zip(impl_.big_params.iter())
+              .all(|(a, p)| *a == Kinded::Ident(p.ident.clone(), vec![])) =>
+        {
+          name
         }
-      }
-      let (ret_type, effects) = match fn_.ret_type.clone() {
-        Kinded::Effectful(t, e) => (*t, flatten(*e)),
-        other => (other, HashSet::new()),
+        _ => return Err(Error::InvalidImpl(impl_.type_.clone())),
       };
-      if let Some(req) = &fn_.ensures {
-        if var_cx.vars.insert(ret_ident(), ret_type.clone()).is_some() {
-          return Err(Error::DuplicateIdentifier(ret_ident()));
+      let bound_types = bound_types(&impl_.bounds);
+      let uses: Vec<_> = std::iter::once(&impl_.type_)
+        .chain(bound_types.iter())
+        .map(|t| (t, Some(Kind::Type)))
+        .chain(impl_.fns.iter().flat_map(sig_uses))
+        .collect();
+      let params = infer_kinds(cx, var_cx, &impl_.big_params, &uses)?;
+      add_big_vars(var_cx, &params)?;
+      add_bounds(cx, var_cx, &impl_.bounds)?;
+      ck_has_kind(cx, var_cx, &impl_.type_, Kind::Type)?;
+      let key = (impl_.trait_.clone(), type_name.clone());
+      if cx.impls.contains_key(&key) {
+        return Err(Error::DuplicateImpl(
+          impl_.trait_.clone(),
+          impl_.type_.clone(),
+        ));
+      }
+      let mut self_vars = HashMap::with_capacity(1);
+      self_vars.insert(self_ident(), impl_.type_.clone());
+      let mut seen = HashSet::with_capacity(impl_.fns.len());
+      for f in impl_.fns.iter() {
+        let (name, want) = match trait_fns
+          .iter()
+          .find(|(name, _)| name.unqualified() == f.name.unqualified())
+        {
+          Some(x) => x,
+          None => return Err(Error::UndefinedIdentifier(f.name.clone())),
+        };
+        if !seen.insert(name) {
+          return Err(Error::DuplicateIdentifier(f.name.clone()));
+        }
+        if !f.big_params.is_empty() {
+          return Err(Error::WrongNumArgs(f.name.clone(), 0, f.big_params.len()));
         }
-        let got = get_expr_type(&cx, &var_cx, req)?;
-        if got.typ != bool_type() {
-          return Err(Error::MismatchedTypes(bool_type(), got.typ));
+        if want.params.len() != f.params.len() {
+          return Err(Error::WrongNumArgs(
+            f.name.clone(),
+            want.params.len(),
+            f.params.len(),
+          ));
         }
-        if let Some(e) = got.effects.into_iter().next() {
-          return Err(Error::InvalidEffectUse(fn_.name.clone(), e));
+        ck_fn_sig(cx, var_cx, f)?;
+        for (w, g) in want.params.iter().zip(f.params.iter()) {
+          ck_type(cx, &subst_kinded(&self_vars, w.type_.clone()), &g.type_)?;
         }
-        var_cx.vars.remove(&ret_ident());
+        ck_type(
+          cx,
+          &subst_kinded(&self_vars, want.ret_type.clone()),
+          &f.ret_type,
+        )?;
       }
-      // register the type here, so that we can have recursive functions.
-      cx.fns.insert(
-        fn_.name.clone(),
-        FnInfo {
-          big_params: fn_.big_params.clone(),
-          params: fn_.params.clone(),
-          ret_type: fn_.ret_type.clone(),
+      if let Some(name) = trait_fns.keys().find(|x| !seen.contains(x)) {
+        return Err(Error::MissingMethod(impl_.trait_.clone(), name.clone()));
+      }
+      rm_bounds(var_cx, &impl_.bounds);
+      rm_big_vars(var_cx, &params);
+      cx.impls.insert(
+        key,
+        ImplInfo {
+          params,
+          bounds: impl_.bounds.clone(),
         },
       );
-      let got = get_block_type(cx, var_cx.clone(), &fn_.body)?;
-      if ret_type != got.typ {
-        return Err(Error::MismatchedTypes(fn_.ret_type.clone(), got.typ));
+    }
+  }
+  Ok(())
+}
+
+/// Checks the bodies of the fns and impls. All signatures must already be registered.
+fn ck_body(cx: &Cx, var_cx: &mut VarCx, td: &TopDefn) -> Result<()> {
+  match td {
+    TopDefn::Fn_(fn_) => {
+      if fn_.body.is_none() && !is_intrinsic(&birb_std_lib::INTRINSIC_FNS, &fn_.name) {
+        return Err(Error::MissingBody(fn_.name.clone()));
       }
-      for e in got.effects {
-        if !effects.contains(&e) {
-          return Err(Error::InvalidEffectUse(fn_.name.clone(), e));
+      ck_fn_body(cx, var_cx, fn_, &cx.fns[&fn_.name])
+    }
+    TopDefn::Impl(impl_) => {
+      let type_name = match &impl_.type_ {
+        Kinded::Ident(name, _) => name,
+        _ => unreachable!("checked in ck_sig"),
+      };
+      let params = &cx.impls[&(impl_.trait_.clone(), type_name.clone())].params;
+      var_cx.module = impl_.fns.first().and_then(|f| module_of(&f.name));
+      add_big_vars(var_cx, params)?;
+      add_bounds(cx, var_cx, &impl_.bounds)?;
+      // only the prelude's impls for the intrinsic types may leave out the bodies.
+      let intrinsic = birb_std_lib::INTRINSIC_IMPLS
+        .iter()
+        .any(|&(t, n)| impl_.trait_ == Ident::new(t) && *type_name == Ident::new(n));
+      for f in impl_.fns.iter() {
+        if f.body.is_none() && !intrinsic {
+          return Err(Error::MissingBody(f.name.clone()));
         }
+        let info = ck_fn_sig(cx, var_cx, f)?;
+        ck_fn_body(cx, var_cx, f, &info)?;
       }
-      for p in fn_.big_params.iter() {
-        var_cx.big_vars.remove(&p.ident).unwrap();
+      rm_bounds(var_cx, &impl_.bounds);
+      rm_big_vars(var_cx, params);
+      Ok(())
+    }
+    _ => Ok(()),
+  }
+}
+
+/// Checks the signature of a fn, returning its info.
+fn ck_fn_sig(cx: &Cx, var_cx: &mut VarCx, fn_: &FnDefn) -> Result<FnInfo> {
+  var_cx.module = module_of(&fn_.name);
+  let bound_types = bound_types(&fn_.bounds);
+  let uses: Vec<_> = sig_uses(fn_)
+    .chain(bound_types.iter().map(|t| (t, Some(Kind::Type))))
+    .collect();
+  let big_params = infer_kinds(cx, var_cx, &fn_.big_params, &uses)?;
+  add_big_vars(var_cx, &big_params)?;
+  add_bounds(cx, var_cx, &fn_.bounds)?;
+  for p in fn_.params.iter() {
+    ck_has_kind(cx, var_cx, &p.type_, Kind::Type)?;
+  }
+  ck_has_kind(cx, var_cx, &fn_.ret_type, Kind::Type)?;
+  rm_bounds(var_cx, &fn_.bounds);
+  rm_big_vars(var_cx, &big_params);
+  Ok(FnInfo {
+    pub_: fn_.pub_,
+    big_params,
+    bounds: fn_.bounds.clone(),
+    params: fn_.params.clone(),
+    ret_type: fn_.ret_type.clone(),
+  })
+}
+
+/// Checks the contracts and body of a fn with the info.
+fn ck_fn_body(cx: &Cx, var_cx: &mut VarCx, fn_: &FnDefn, info: &FnInfo) -> Result<()> {
+  let allowed = match expand(cx, &info.ret_type) {
+    Kinded::Effectful(_, e) => flatten(*e),
+    _ => HashSet::new(),
+  };
+  for e in get_fn_effects(cx, var_cx, fn_, info)? {
+    if !allowed.contains(&e) {
+      return Err(Error::InvalidEffectUse(fn_.name.clone(), e));
+    }
+  }
+  Ok(())
+}
+
+/// Checks the contracts and body of a fn with the info, except for the effects. Returns the effects
+/// the body uses.
+fn get_fn_effects(
+  cx: &Cx,
+  var_cx: &mut VarCx,
+  fn_: &FnDefn,
+  info: &FnInfo,
+) -> Result<HashSet<Kinded>> {
+  var_cx.module = module_of(&fn_.name);
+  add_big_vars(var_cx, &info.big_params)?;
+  add_bounds(cx, var_cx, &fn_.bounds)?;
+  if birb_std_lib::KEYED
+    .iter()
+    .any(|&x| fn_.name == Ident::new(x))
+  {
+    var_cx.comparable.insert(info.big_params[0].ident.clone());
+  }
+  for p in fn_.params.iter() {
+    if var_cx
+      .vars
+      .insert(p.ident.clone(), p.type_.clone())
+      .is_some()
+    {
+      return Err(Error::DuplicateIdentifier(p.ident.clone()));
+    }
+  }
+  if let Some(req) = &fn_.requires {
+    let got = ck_expr(cx, var_cx, req, &bool_type())?;
+    if let Some(e) = got.effects.into_iter().next() {
+      return Err(Error::InvalidEffectUse(fn_.name.clone(), e));
+    }
+  }
+  let ret_type = match expand(cx, &info.ret_type) {
+    Kinded::Effectful(t, _) => *t,
+    _ => info.ret_type.clone(),
+  };
+  if let Some(req) = &fn_.ensures {
+    if var_cx.vars.insert(ret_ident(), ret_type.clone()).is_some() {
+      return Err(Error::DuplicateIdentifier(ret_ident()));
+    }
+    let got = ck_expr(cx, var_cx, req, &bool_type())?;
+    if let Some(e) = got.effects.into_iter().next() {
+      return Err(Error::InvalidEffectUse(fn_.name.clone(), e));
+    }
+    var_cx.vars.remove(&ret_ident());
+  }
+  let effects = match &fn_.body {
+    // checked by the caller to be an intrinsic.
+    None => HashSet::new(),
+    Some(body) => {
+      let mut body_cx = var_cx.clone();
+      body_cx.ret_type = Some(ret_type.clone());
+      let got = get_block_type(cx, body_cx, body, Some(&ret_type))?;
+      ck_type(cx, &ret_type, &got.typ)?;
+      got.effects
+    }
+  };
+  rm_bounds(var_cx, &fn_.bounds);
+  rm_big_vars(var_cx, &info.big_params);
+  var_cx.comparable.clear();
+  var_cx.vars.clear();
+  Ok(effects)
+}
+
+/// Infers the effects of the fns which are not `pub` and have no `affects`, from their bodies, and
+/// adds them to the return types of the fns. Returns the inferred effects of each such fn.
+fn infer_effects(
+  cx: &mut Cx,
+  var_cx: &mut VarCx,
+  top_defns: &[&TopDefn],
+) -> Result<Vec<(Ident, Kinded)>> {
+  let fns: Vec<_> = top_defns
+    .iter()
+    .filter_map(|td| match td {
+      TopDefn::Fn_(fn_)
+        if !fn_.pub_
+          && fn_.name != Ident::new("main")
+          && fn_.body.is_some()
+          && !matches!(expand(cx, &fn_.ret_type), Kinded::Effectful(..)) =>
+      {
+        Some(&**fn_)
       }
-      for p in fn_.params.iter() {
-        var_cx.vars.remove(&p.ident).unwrap();
+      _ => None,
+    })
+    .collect();
+  let mut inferred: HashMap<&Ident, HashSet<Kinded>> = HashMap::with_capacity(fns.len());
+  // the fns may call each other, so the effects grow until they reach a fixpoint.
+  loop {
+    let mut changed = false;
+    for fn_ in fns.iter() {
+      let info = cx.fns[&fn_.name].clone();
+      let effects = get_fn_effects(cx, var_cx, fn_, &info)?;
+      if inferred.get(&fn_.name) == Some(&effects) {
+        continue;
       }
+      changed = true;
+      let info = cx.fns.get_mut(&fn_.name).unwrap();
+      info.ret_type = if effects.is_empty() {
+        fn_.ret_type.clone()
+      } else {
+        Kinded::Effectful(fn_.ret_type.clone().into(), effect_set(&effects).into())
+      };
+      inferred.insert(&fn_.name, effects);
+    }
+    if !changed {
+      break;
+    }
+  }
+  let ret = fns
+    .iter()
+    .map(|fn_| (fn_.name.clone(), effect_set(&inferred[&fn_.name])))
+    .collect();
+  Ok(ret)
+}
+
+/// Returns the effects as a set, in order.
+fn effect_set(effects: &HashSet<Kinded>) -> Kinded {
+  let mut effects: Vec<_> = effects.iter().cloned().collect();
+  effects.sort_by_key(|e| e.to_string());
+  Kinded::Set(effects)
+}
+
+/// Adds the big params as big vars, erroring if any is already one.
+fn add_big_vars(var_cx: &mut VarCx, params: &[Param<Ident, Kind>]) -> Result<()> {
+  for p in params {
+    if var_cx
+      .big_vars
+      .insert(p.ident.clone(), p.type_.clone())
+      .is_some()
+    {
+      return Err(Error::DuplicateIdentifier(p.ident.clone()));
     }
   }
   Ok(())
 }
 
+fn rm_big_vars(var_cx: &mut VarCx, params: &[Param<Ident, Kind>]) {
+  for p in params {
+    var_cx.big_vars.remove(&p.ident).unwrap();
+  }
+}
+
+/// Adds the bounds on the big vars, like `T: Eq`. The big vars must already be added.
+fn add_bounds(cx: &Cx, var_cx: &mut VarCx, bounds: &[Param<Ident, Ident>]) -> Result<()> {
+  for b in bounds {
+    match var_cx.big_vars.get(&b.ident) {
+      Some(Kind::Type) => {}
+      Some(k) => return Err(Error::MismatchedKinds(Kind::Type, k.clone())),
+      None => return Err(Error::UndefinedIdentifier(b.ident.clone())),
+    }
+    match cx.traits.get(&b.type_) {
+      Some(info) => ck_visible(var_cx, &b.type_, info.pub_)?,
+      None => return Err(Error::UndefinedIdentifier(b.type_.clone())),
+    }
+    var_cx.bounds.insert((b.ident.clone(), b.type_.clone()));
+  }
+  Ok(())
+}
+
+fn rm_bounds(var_cx: &mut VarCx, bounds: &[Param<Ident, Ident>]) {
+  for b in bounds {
+    var_cx.bounds.remove(&(b.ident.clone(), b.type_.clone()));
+  }
+}
+
+/// Checks that the type implements the trait.
+fn ck_impl(cx: &Cx, var_cx: &VarCx, trait_: &Ident, typ: &Kinded) -> Result<()> {
+  if let Kinded::Ident(name, args) = typ {
+    if var_cx.big_vars.contains_key(name) {
+      if var_cx.bounds.contains(&(name.clone(), trait_.clone())) {
+        return Ok(());
+      }
+    } else if let Some(info) = cx.impls.get(&(trait_.clone(), name.clone())) {
+      // the impl may need its big params to implement traits too, like `impl[T: Type + Eq] Eq for
+      // Option[T]`.
+      let big_vars: HashMap<_, _> = info
+        .params
+        .iter()
+        .zip(args)
+        .map(|(p, a)| (p.ident.clone(), a))
+        .collect();
+      for b in info.bounds.iter() {
+        ck_impl(cx, var_cx, &b.type_, big_vars[&b.ident])?;
+      }
+      return Ok(());
+    }
+  }
+  Err(Error::NoImpl(trait_.clone(), typ.clone()))
+}
+
+/// Returns the trait name, trait info, and fn info for the fn of a trait.
+fn get_trait_fn<'a>(cx: &'a Cx, name: &Ident) -> Option<(&'a Ident, &'a TraitInfo, &'a FnInfo)> {
+  cx.traits
+    .iter()
+    .find_map(|(trait_, info)| info.fns.get(name).map(|f| (trait_, info, f)))
+}
+
+/// Returns whether the name is defined in one of the packages.
+fn in_packages(packages: &HashSet<Ident>, name: &Ident) -> bool {
+  name
+    .split_first()
+    .is_some_and(|(package, _)| packages.contains(&package))
+}
+
+/// Returns the module of the definition with this name, which is None for the main file.
+fn module_of(name: &Ident) -> Option<Ident> {
+  name.split().map(|(module, _)| module)
+}
+
+/// Checks that the definition with this name may be used in the current module.
+fn ck_visible(var_cx: &VarCx, name: &Ident, pub_: bool) -> Result<()> {
+  if pub_ || module_of(name) == var_cx.module {
+    Ok(())
+  } else {
+    Err(Error::Private(name.clone()))
+  }
+}
+
 fn get_kind(cx: &Cx, var_cx: &VarCx, kinded: &Kinded) -> Result<Kind> {
   match kinded {
     Kinded::Ident(bi, args) => {
-      let k = if let Some(si) = cx.structs.get(bi) {
-        mk_params_kind(&si.params)
+      let (k, args) = if let Some(ai) = cx.aliases.get(bi) {
+        ck_visible(var_cx, bi, ai.pub_)?;
+        // aliases must always be given all their args. they may be given more if they are for a
+        // type constructor, like `type L = List;`.
+        if args.len() < ai.params.len() {
+          return Err(Error::WrongNumArgs(bi.clone(), ai.params.len(), args.len()));
+        }
+        let (alias_args, args) = args.split_at(ai.params.len());
+        for (p, a) in ai.params.iter().zip(alias_args) {
+          ck_has_kind(cx, var_cx, a, p.type_.clone())?;
+        }
+        (ai.kind.clone(), args)
+      } else if let Some(si) = cx.structs.get(bi) {
+        ck_visible(var_cx, bi, si.pub_)?;
+        (mk_params_kind(&si.params), &args[..])
       } else if let Some(ei) = cx.enums.get(bi) {
-        mk_params_kind(&ei.params)
+        ck_visible(var_cx, bi, ei.pub_)?;
+        (mk_params_kind(&ei.params), &args[..])
+      } else if let Some(ti) = cx.types.get(bi) {
+        ck_visible(var_cx, bi, ti.pub_)?;
+        (mk_params_kind(&ti.params), &args[..])
       } else if cx.effects.contains(bi) {
-        Kind::Effect
+        (Kind::Effect, &args[..])
       } else if let Some(k) = var_cx.big_vars.get(bi) {
-        k.clone()
+        (k.clone(), &args[..])
       } else {
         return Err(Error::UndefinedIdentifier(bi.clone()));
       };
@@ -246,6 +981,22 @@ fn get_kind(cx: &Cx, var_cx: &VarCx, kinded: &Kinded) -> Result<Kind> {
       } else {
         return Err(Error::InvalidKindedApp(bi.clone(), k));
       };
+      // partial application of something with many params, like `Result[Str]`, which has kind
+      // `Type -> Type`.
+      if let Kind::Tuple(ps) = &param {
+        if args.len() < ps.len() {
+          for (p, a) in ps.iter().zip(args) {
+            ck_has_kind(cx, var_cx, a, p.clone())?;
+          }
+          let mut rest = ps[args.len()..].to_vec();
+          let rest = if rest.len() == 1 {
+            rest.pop().unwrap()
+          } else {
+            Kind::Tuple(rest)
+          };
+          return Ok(Kind::Arrow(rest.into(), res.into()));
+        }
+      }
       let mut arg_kinds = Vec::with_capacity(args.len());
       for arg in args {
         arg_kinds.push(get_kind(cx, var_cx, arg)?);
@@ -274,38 +1025,449 @@ fn get_kind(cx: &Cx, var_cx: &VarCx, kinded: &Kinded) -> Result<Kind> {
       }
       Ok(Kind::Effect)
     }
-    Kinded::Arrow(t1, t2) => {
-      ck_has_kind(cx, var_cx, t1, Kind::Type)?;
-      ck_has_kind(cx, var_cx, t2, Kind::Type)?;
-      Ok(Kind::Type)
+    Kinded::Arrow(t1, t2) => {
+      ck_has_kind(cx, var_cx, t1, Kind::Type)?;
+      ck_has_kind(cx, var_cx, t2, Kind::Type)?;
+      Ok(Kind::Type)
+    }
+    Kinded::Effectful(t, e) => {
+      ck_has_kind(cx, var_cx, t, Kind::Type)?;
+      ck_has_kind(cx, var_cx, e, Kind::Effect)?;
+      Ok(Kind::Type)
+    }
+    Kinded::Never => Ok(Kind::Type),
+  }
+}
+
+fn ck_has_kind(cx: &Cx, var_cx: &VarCx, kinded: &Kinded, want: Kind) -> Result<()> {
+  let got = get_kind(cx, var_cx, kinded)?;
+  if want == got {
+    Ok(())
+  } else {
+    Err(Error::MismatchedKinds(want, got))
+  }
+}
+
+/// Checks that values of this type can be compared for equality and ordering, which is to say the
+/// type is made only of data, and not functions or type variables (which could be functions),
+/// unless the type variables are bounded by Eq and Ord. Map and Set keys are still compared
+/// structurally, not with the impls.
+fn ck_comparable(
+  cx: &Cx,
+  var_cx: &VarCx,
+  typ: &Kinded,
+  visiting: &mut HashSet<Ident>,
+) -> Result<()> {
+  match typ {
+    Kinded::Ident(name, args) => {
+      for a in args {
+        ck_comparable(cx, var_cx, a, visiting)?;
+      }
+      let bounded = |t| var_cx.bounds.contains(&(name.clone(), Ident::new(t)));
+      if var_cx.comparable.contains(name)
+        || (bounded(birb_std_lib::EQ_TRAIT) && bounded(birb_std_lib::ORD_TRAIT))
+      {
+        return Ok(());
+      }
+      // assume recursive uses are fine, so we don't loop forever.
+      if !visiting.insert(name.clone()) {
+        return Ok(());
+      }
+      let (params, field_types): (_, Vec<_>) = if let Some(info) = cx.structs.get(name) {
+        (&info.params, info.fields.values().collect())
+      } else if let Some(info) = cx.enums.get(name) {
+        (
+          &info.params,
+          info.ctors.values().flat_map(CtorInfo::types).collect(),
+        )
+      } else if cx.types.contains_key(name) {
+        // all the intrinsic types are comparable, given their args are.
+        return Ok(());
+      } else {
+        return Err(Error::NotComparable(typ.clone()));
+      };
+      let big_vars: HashMap<_, _> = params
+        .iter()
+        .zip(args)
+        .map(|(p, a)| (p.ident.clone(), a.clone()))
+        .collect();
+      for t in field_types {
+        let t = expand(cx, &subst_kinded(&big_vars, t.clone()));
+        ck_comparable(cx, var_cx, &t, visiting).map_err(|_| Error::NotComparable(typ.clone()))?;
+      }
+      visiting.remove(name);
+      Ok(())
+    }
+    Kinded::Tuple(ts) => {
+      for t in ts {
+        ck_comparable(cx, var_cx, t, visiting)?;
+      }
+      Ok(())
+    }
+    Kinded::Set(..) | Kinded::Arrow(..) | Kinded::Effectful(..) => {
+      Err(Error::NotComparable(typ.clone()))
+    }
+    Kinded::Never => Ok(()),
+  }
+}
+
+fn mk_params_kind(params: &[Param<Ident, Kind>]) -> Kind {
+  if params.is_empty() {
+    Kind::Type
+  } else if params.len() == 1 {
+    Kind::Arrow(params[0].type_.clone().into(), Kind::Type.into())
+  } else {
+    Kind::Arrow(
+      Kind::Tuple(params.iter().map(|p| p.type_.clone()).collect()).into(),
+      Kind::Type.into(),
+    )
+  }
+}
+
+/// Returns the params with their kinds, inferring the kinds which were not written from how the
+/// params are used. The uses are types with the kinds they must have, or None for any kind. Kinds
+/// which can't be inferred default to Type.
+fn infer_kinds(
+  cx: &Cx,
+  var_cx: &VarCx,
+  params: &[Param<Ident, Option<Kind>>],
+  uses: &[(&Kinded, Option<Kind>)],
+) -> Result<Vec<Param<Ident, Kind>>> {
+  let mut subst = KindSubst::default();
+  let vars = param_kinds(&mut subst, params);
+  // if every kind was written, leave reporting errors to the usual checks.
+  if params.iter().any(|p| p.type_.is_none()) {
+    infer_uses(cx, var_cx, params, &vars, &mut subst, uses)?;
+  }
+  Ok(solved_params(&subst, params, &vars))
+}
+
+/// Like infer_kinds, but for the params of several structs and enums at once, which may use each
+/// other. Returns the params by the name of their struct or enum.
+fn infer_data_kinds(
+  cx: &Cx,
+  var_cx: &VarCx,
+  datas: &[DataUses<'_>],
+) -> Result<HashMap<Ident, Vec<Param<Ident, Kind>>>> {
+  let mut subst = KindSubst::default();
+  let all_vars: Vec<_> = datas
+    .iter()
+    .map(|d| param_kinds(&mut subst, d.params))
+    .collect();
+  let heads: HashMap<_, _> = datas
+    .iter()
+    .zip(all_vars.iter())
+    .map(|(d, vars)| {
+      let ks: Vec<_> = d.params.iter().map(|p| vars[&p.ident].clone()).collect();
+      (d.name.clone(), mk_params_infer_kind(ks))
+    })
+    .collect();
+  if datas
+    .iter()
+    .any(|d| d.params.iter().any(|p| p.type_.is_none()))
+  {
+    for (d, vars) in datas.iter().zip(all_vars.iter()) {
+      // the params shadow the data types.
+      let mut vars = vars.clone();
+      for (name, k) in heads.iter() {
+        vars.entry(name.clone()).or_insert_with(|| k.clone());
+      }
+      infer_uses(cx, var_cx, d.params, &vars, &mut subst, &d.uses)?;
+    }
+  }
+  let ret = datas
+    .iter()
+    .zip(all_vars.iter())
+    .map(|(d, vars)| (d.name.clone(), solved_params(&subst, d.params, vars)))
+    .collect();
+  Ok(ret)
+}
+
+/// Returns the kinds of the params, with unknowns for those which were not written.
+fn param_kinds(
+  subst: &mut KindSubst,
+  params: &[Param<Ident, Option<Kind>>],
+) -> HashMap<Ident, InferKind> {
+  params
+    .iter()
+    .map(|p| {
+      let k = match &p.type_ {
+        Some(k) => InferKind::from(k.clone()),
+        None => subst.fresh(),
+      };
+      (p.ident.clone(), k)
+    })
+    .collect()
+}
+
+/// Infers the kinds of the vars from the uses. A use which conflicts with an earlier use of one of
+/// the params whose kind is inferred is reported with both uses.
+fn infer_uses(
+  cx: &Cx,
+  var_cx: &VarCx,
+  params: &[Param<Ident, Option<Kind>>],
+  vars: &HashMap<Ident, InferKind>,
+  subst: &mut KindSubst,
+  uses: &[(&Kinded, Option<Kind>)],
+) -> Result<()> {
+  let mut first_uses: HashMap<&Ident, &Kinded> = HashMap::new();
+  for &(kinded, ref want) in uses {
+    let want = match want {
+      Some(k) => InferKind::from(k.clone()),
+      None => subst.fresh(),
+    };
+    let inferred = params
+      .iter()
+      .filter(|p| p.type_.is_none() && mentions(kinded, &p.ident));
+    if let Err(e) = infer_kinded(cx, var_cx, vars, subst, kinded, want) {
+      let first = inferred
+        .clone()
+        .find_map(|p| Some((&p.ident, first_uses.get(&p.ident)?)));
+      return Err(match (e, first) {
+        (Error::MismatchedKinds(want, got), Some((param, &first))) => Error::MismatchedParamKinds(
+          param.clone(),
+          Box::new((first.clone(), kinded.clone())),
+          Box::new((want, got)),
+        ),
+        (e, _) => e,
+      });
+    }
+    for p in inferred {
+      first_uses.entry(&p.ident).or_insert(kinded);
+    }
+  }
+  Ok(())
+}
+
+fn solved_params(
+  subst: &KindSubst,
+  params: &[Param<Ident, Option<Kind>>],
+  vars: &HashMap<Ident, InferKind>,
+) -> Vec<Param<Ident, Kind>> {
+  params
+    .iter()
+    .map(|p| Param {
+      ident: p.ident.clone(),
+      type_: subst.get(&vars[&p.ident]),
+    })
+    .collect()
+}
+
+/// Like mk_params_kind, for kinds which may have unknown parts.
+fn mk_params_infer_kind(mut ks: Vec<InferKind>) -> InferKind {
+  let param = match ks.len() {
+    0 => return InferKind::Type,
+    1 => ks.pop().unwrap(),
+    _ => InferKind::Tuple(ks),
+  };
+  InferKind::Arrow(param.into(), InferKind::Type.into())
+}
+
+fn infer_kinded(
+  cx: &Cx,
+  var_cx: &VarCx,
+  vars: &HashMap<Ident, InferKind>,
+  subst: &mut KindSubst,
+  kinded: &Kinded,
+  want: InferKind,
+) -> Result<()> {
+  match kinded {
+    Kinded::Ident(bi, args) => {
+      // look up names in the same order as get_kind.
+      let head = if let Some(k) = global_kind(cx, bi) {
+        InferKind::from(k)
+      } else if let Some(k) = vars.get(bi) {
+        k.clone()
+      } else if let Some(k) = var_cx.big_vars.get(bi) {
+        InferKind::from(k.clone())
+      } else {
+        // undefined, which is reported later.
+        return Ok(());
+      };
+      if args.is_empty() {
+        return subst.unify(want, head);
+      }
+      // partial application, like in get_kind.
+      if let InferKind::Arrow(param, res) = subst.prune(head.clone()) {
+        if let InferKind::Tuple(ps) = subst.prune(*param) {
+          if args.len() < ps.len() {
+            for (p, arg) in ps.iter().zip(args) {
+              infer_kinded(cx, var_cx, vars, subst, arg, p.clone())?;
+            }
+            let mut rest = ps[args.len()..].to_vec();
+            let rest = if rest.len() == 1 {
+              rest.pop().unwrap()
+            } else {
+              InferKind::Tuple(rest)
+            };
+            return subst.unify(want, InferKind::Arrow(rest.into(), res));
+          }
+        }
+      }
+      let mut arg_kinds = Vec::with_capacity(args.len());
+      for arg in args {
+        let k = subst.fresh();
+        infer_kinded(cx, var_cx, vars, subst, arg, k.clone())?;
+        arg_kinds.push(k);
+      }
+      let arg_kind = if arg_kinds.len() == 1 {
+        arg_kinds.pop().unwrap()
+      } else {
+        InferKind::Tuple(arg_kinds)
+      };
+      subst.unify(InferKind::Arrow(arg_kind.into(), want.into()), head)
+    }
+    Kinded::Tuple(ts) => {
+      subst.unify(want, InferKind::Type)?;
+      for t in ts {
+        infer_kinded(cx, var_cx, vars, subst, t, InferKind::Type)?;
+      }
+      Ok(())
+    }
+    Kinded::Set(es) => {
+      subst.unify(want, InferKind::Effect)?;
+      for e in es {
+        infer_kinded(cx, var_cx, vars, subst, e, InferKind::Effect)?;
+      }
+      Ok(())
+    }
+    Kinded::Arrow(t1, t2) => {
+      subst.unify(want, InferKind::Type)?;
+      infer_kinded(cx, var_cx, vars, subst, t1, InferKind::Type)?;
+      infer_kinded(cx, var_cx, vars, subst, t2, InferKind::Type)
+    }
+    Kinded::Effectful(t, e) => {
+      subst.unify(want, InferKind::Type)?;
+      infer_kinded(cx, var_cx, vars, subst, t, InferKind::Type)?;
+      infer_kinded(cx, var_cx, vars, subst, e, InferKind::Effect)
+    }
+    Kinded::Never => subst.unify(want, InferKind::Type),
+  }
+}
+
+/// Returns the kind of a top-level type or effect. Aliases are treated as having an arrow kind if
+/// they have params.
+fn global_kind(cx: &Cx, bi: &Ident) -> Option<Kind> {
+  let ret = if let Some(info) = cx.aliases.get(bi) {
+    match mk_params_kind(&info.params) {
+      Kind::Arrow(param, _) => Kind::Arrow(param, info.kind.clone().into()),
+      _ => info.kind.clone(),
+    }
+  } else if let Some(info) = cx.structs.get(bi) {
+    mk_params_kind(&info.params)
+  } else if let Some(info) = cx.enums.get(bi) {
+    mk_params_kind(&info.params)
+  } else if let Some(info) = cx.types.get(bi) {
+    mk_params_kind(&info.params)
+  } else if cx.effects.contains(bi) {
+    Kind::Effect
+  } else {
+    return None;
+  };
+  Some(ret)
+}
+
+/// The types in the signature of a fn, which must have kind Type.
+fn sig_uses(fn_: &FnDefn) -> impl Iterator<Item = (&Kinded, Option<Kind>)> {
+  fn_
+    .params
+    .iter()
+    .map(|p| &p.type_)
+    .chain(std::iter::once(&fn_.ret_type))
+    .map(|t| (t, Some(Kind::Type)))
+}
+
+/// The big vars with bounds, which must have kind Type.
+fn bound_types(bounds: &[Param<Ident, Ident>]) -> Vec<Kinded> {
+  bounds
+    .iter()
+    .map(|b| Kinded::Ident(b.ident.clone(), vec![]))
+    .collect()
+}
+
+/// A kind which may have unknown parts, for inferring the kinds of big params.
+#[derive(Debug, Clone)]
+enum InferKind {
+  Type,
+  Effect,
+  Arrow(Box<InferKind>, Box<InferKind>),
+  Tuple(Vec<InferKind>),
+  /// An unknown kind.
+  Var(usize),
+}
+
+impl From<Kind> for InferKind {
+  fn from(k: Kind) -> Self {
+    match k {
+      Kind::Type => Self::Type,
+      Kind::Effect => Self::Effect,
+      Kind::Arrow(k1, k2) => Self::Arrow(Self::from(*k1).into(), Self::from(*k2).into()),
+      Kind::Tuple(ks) => Self::Tuple(ks.into_iter().map(Self::from).collect()),
+    }
+  }
+}
+
+/// The solutions for the unknown kinds.
+#[derive(Default)]
+struct KindSubst(Vec<Option<InferKind>>);
+
+impl KindSubst {
+  fn fresh(&mut self) -> InferKind {
+    self.0.push(None);
+    InferKind::Var(self.0.len() - 1)
+  }
+
+  /// Replaces solved unknowns at the top of the kind with their solutions.
+  fn prune(&self, k: InferKind) -> InferKind {
+    match k {
+      InferKind::Var(v) => match &self.0[v] {
+        Some(k) => self.prune(k.clone()),
+        None => k,
+      },
+      _ => k,
     }
-    Kinded::Effectful(t, e) => {
-      ck_has_kind(cx, var_cx, t, Kind::Type)?;
-      ck_has_kind(cx, var_cx, e, Kind::Effect)?;
-      Ok(Kind::Type)
+  }
+
+  fn occurs(&self, v: usize, k: &InferKind) -> bool {
+    match self.prune(k.clone()) {
+      InferKind::Type | InferKind::Effect => false,
+      InferKind::Arrow(k1, k2) => self.occurs(v, &k1) || self.occurs(v, &k2),
+      InferKind::Tuple(ks) => ks.iter().any(|k| self.occurs(v, k)),
+      InferKind::Var(w) => v == w,
     }
   }
-}
 
-fn ck_has_kind(cx: &Cx, var_cx: &VarCx, kinded: &Kinded, want: Kind) -> Result<()> {
-  let got = get_kind(cx, var_cx, kinded)?;
-  if want == got {
-    Ok(())
-  } else {
-    Err(Error::MismatchedKinds(want, got))
+  /// Unifies the kinds. Errors, like MismatchedKinds, with the wanted kind first.
+  fn unify(&mut self, want: InferKind, got: InferKind) -> Result<()> {
+    match (self.prune(want), self.prune(got)) {
+      (InferKind::Type, InferKind::Type) | (InferKind::Effect, InferKind::Effect) => Ok(()),
+      (InferKind::Var(v), InferKind::Var(w)) if v == w => Ok(()),
+      (InferKind::Var(v), k) | (k, InferKind::Var(v)) if !self.occurs(v, &k) => {
+        self.0[v] = Some(k);
+        Ok(())
+      }
+      (InferKind::Arrow(w1, w2), InferKind::Arrow(g1, g2)) => {
+        self.unify(*w1, *g1)?;
+        self.unify(*w2, *g2)
+      }
+      (InferKind::Tuple(ws), InferKind::Tuple(gs)) if ws.len() == gs.len() => {
+        for (w, g) in ws.into_iter().zip(gs) {
+          self.unify(w, g)?;
+        }
+        Ok(())
+      }
+      (want, got) => Err(Error::MismatchedKinds(self.get(&want), self.get(&got))),
+    }
   }
-}
 
-fn mk_params_kind(params: &[Param<Ident, Kind>]) -> Kind {
-  if params.is_empty() {
-    Kind::Type
-  } else if params.len() == 1 {
-    Kind::Arrow(params[0].type_.clone().into(), Kind::Type.into())
-  } else {
-    Kind::Arrow(
-      Kind::Tuple(params.iter().map(|p| p.type_.clone()).collect()).into(),
-      Kind::Type.into(),
-    )
+  /// Returns the kind, with unknowns which have no solution defaulting to Type.
+  fn get(&self, k: &InferKind) -> Kind {
+    match self.prune(k.clone()) {
+      InferKind::Type | InferKind::Var(_) => Kind::Type,
+      InferKind::Effect => Kind::Effect,
+      InferKind::Arrow(k1, k2) => Kind::Arrow(self.get(&k1).into(), self.get(&k2).into()),
+      InferKind::Tuple(ks) => Kind::Tuple(ks.iter().map(|k| self.get(k)).collect()),
+    }
   }
 }
 
@@ -327,58 +1489,124 @@ impl ExprRes {
   }
 }
 
+/// Infers the type of the expression.
 fn get_expr_type(cx: &Cx, var_cx: &VarCx, expr: &Expr) -> Result<ExprRes> {
+  expr_type(cx, var_cx, expr, None)
+}
+
+/// Checks that the expression has the type. The type is used to check the parts of the expression,
+/// so that, for instance, the big args of `none(())` can be inferred from it.
+fn ck_expr(cx: &Cx, var_cx: &VarCx, expr: &Expr, want: &Kinded) -> Result<ExprRes> {
+  let got = expr_type(cx, var_cx, expr, Some(want))?;
+  ck_type(cx, want, &got.typ)?;
+  Ok(got)
+}
+
+/// Returns the type of the expression, which should be `want` if it is known. The returned type
+/// need not be `want`, so callers must check that it is.
+fn expr_type(cx: &Cx, var_cx: &VarCx, expr: &Expr, want: Option<&Kinded>) -> Result<ExprRes> {
+  let want_exp = want.map(|t| expand(cx, t));
   match expr {
     Expr::String_(_) => Ok(ExprRes::pure_(str_type())),
     Expr::Number(_) => Ok(ExprRes::pure_(nat_type())),
     Expr::Tuple(es) => {
+      let wants = match want_exp {
+        Some(Kinded::Tuple(ts)) if ts.len() == es.len() => ts.into_iter().map(Some).collect(),
+        _ => vec![None; es.len()],
+      };
       let mut types = Vec::with_capacity(es.len());
       let mut effects = HashSet::new();
-      for e in es {
-        let res = get_expr_type(cx, var_cx, e)?;
+      for (e, want) in es.iter().zip(wants) {
+        let res = match want {
+          Some(want) => ck_expr(cx, var_cx, e, &want)?,
+          None => get_expr_type(cx, var_cx, e)?,
+        };
         types.push(res.typ);
         effects.extend(res.effects);
       }
       Ok(ExprRes::effectful(Kinded::Tuple(types), effects))
     }
-    Expr::Struct(name, args, fields) => {
-      let info = match cx.structs.get(name) {
-        Some(x) => x.clone(),
-        None => return Err(Error::UndefinedIdentifier(name.clone())),
+    Expr::List(es) => {
+      if let Some(Kinded::Ident(name, mut args)) = want_exp {
+        if name == Ident::new(birb_std_lib::LIST) {
+          let elem_type = args.pop().unwrap();
+          let mut effects = HashSet::new();
+          for e in es {
+            effects.extend(ck_expr(cx, var_cx, e, &elem_type)?.effects);
+          }
+          return Ok(ExprRes::effectful(list_type(elem_type), effects));
+        }
+      }
+      let mut iter = es.iter();
+      let first = match iter.next() {
+        Some(e) => get_expr_type(cx, var_cx, e)?,
+        None => return Err(Error::EmptyList),
       };
-      if info.params.len() != args.len() {
-        return Err(Error::WrongNumArgs(
-          name.clone(),
-          info.params.len(),
-          args.len(),
-        ));
+      let mut elem_type = first.typ;
+      let mut effects = first.effects;
+      for e in iter {
+        let got = get_expr_type(cx, var_cx, e)?;
+        elem_type = join(cx, elem_type, got.typ)?;
+        effects.extend(got.effects);
       }
-      for (p, a) in info.params.iter().zip(args) {
-        ck_has_kind(&cx, &var_cx, a, p.type_.clone())?;
+      Ok(ExprRes::effectful(list_type(elem_type), effects))
+    }
+    Expr::Struct(name, args, fields, base) => {
+      // either a struct, or a ctor with named fields.
+      let (params, info_fields, typ) = if let Some(info) = cx.structs.get(name) {
+        ck_visible(var_cx, name, info.pub_)?;
+        let typ = Kinded::Ident(name.clone(), args.clone());
+        (&info.params, &info.fields, typ)
+      } else if let Some((enum_name, enum_info, ctor)) = get_ctor(cx, name)? {
+        ck_visible(var_cx, enum_name, enum_info.pub_)?;
+        let fields = match ctor {
+          CtorInfo::Named(fields) if base.is_none() => fields,
+          _ => return Err(Error::NotStructName(name.clone())),
+        };
+        let typ = Kinded::Ident(enum_name.clone(), args.clone());
+        (&enum_info.params, fields, typ)
+      } else {
+        return Err(Error::UndefinedIdentifier(name.clone()));
+      };
+      if params.len() != args.len() {
+        return Err(Error::WrongNumArgs(name.clone(), params.len(), args.len()));
       }
-      let mut fields_seen = HashSet::with_capacity(info.fields.len());
+      let mut big_vars = HashMap::with_capacity(args.len());
+      for (p, a) in params.iter().zip(args) {
+        ck_has_kind(cx, var_cx, a, p.type_.clone())?;
+        big_vars.insert(p.ident.clone(), a.clone());
+      }
+      let mut fields_seen = HashSet::with_capacity(info_fields.len());
       let mut effects = HashSet::new();
       for f in fields {
-        let (x, got) = match f {
-          Field::Ident(x) => (x, get_expr_type(cx, var_cx, &Expr::Ident(x.clone()))?),
-          Field::IdentAnd(x, e) => (x, get_expr_type(cx, var_cx, e)?),
+        let x = match f {
+          Field::Ident(x) | Field::IdentAnd(x, _) => x,
         };
-        let want = match info.fields.get(x) {
+        let want = match info_fields.get(x) {
           None => return Err(Error::NoSuchField(name.clone(), x.clone())),
-          Some(t) => t,
+          Some(t) => subst_kinded(&big_vars, t.clone()),
+        };
+        let got = match f {
+          Field::Ident(x) => ck_expr(cx, var_cx, &Expr::Ident(x.clone()), &want)?,
+          Field::IdentAnd(_, e) => ck_expr(cx, var_cx, e, &want)?,
         };
-        if *want != got.typ {
-          return Err(Error::MismatchedTypes(want.clone(), got.typ));
-        }
         if !fields_seen.insert(x) {
           return Err(Error::DuplicateField(name.clone(), x.clone()));
         }
         effects.extend(got.effects);
       }
-      Ok(ExprRes::effectful(
-        Kinded::Ident(name.clone(), args.clone()),
-        effects,
-      ))
+      match base {
+        Some(base) => {
+          let got = ck_expr(cx, var_cx, base, &typ)?;
+          effects.extend(got.effects);
+        }
+        None => {
+          if let Some(x) = info_fields.keys().find(|x| !fields_seen.contains(x)) {
+            return Err(Error::MissingField(name.clone(), x.clone()));
+          }
+        }
+      }
+      Ok(ExprRes::effectful(typ, effects))
     }
     Expr::Ident(name) => {
       if let Some(t) = var_cx.vars.get(name) {
@@ -388,15 +1616,64 @@ fn get_expr_type(cx: &Cx, var_cx: &VarCx, expr: &Expr) -> Result<ExprRes> {
       Err(Error::UndefinedIdentifier(name.clone()))
     }
     Expr::FnCall(name, big_args, args) => {
+      // the args which were already checked, while finding the impl or inferring the big args.
+      let mut got_args = Vec::new();
       let info = if let Some(info) = cx.fns.get(name) {
+        ck_visible(var_cx, name, info.pub_)?;
         info.clone()
-      } else if let Some(info) = cx.enums.iter().find_map(|(enum_name, enum_info)| {
-        enum_info.ctors.get(name).map(|type_| FnInfo {
+      } else if let Some((trait_, trait_info, info)) = get_trait_fn(cx, name) {
+        ck_visible(var_cx, trait_, trait_info.pub_)?;
+        // the type of the first arg is the type implementing the trait.
+        let self_type = match args.first() {
+          Some(a) => {
+            let got = get_expr_type(cx, var_cx, a)?;
+            let typ = expand(cx, &got.typ);
+            got_args.push(got);
+            typ
+          }
+          None => return Err(Error::WrongNumArgs(name.clone(), info.params.len(), 0)),
+        };
+        if let Err(e) = ck_impl(cx, var_cx, trait_, &self_type) {
+          // types without an impl of Eq are compared structurally, if they can be.
+          let structural = *trait_ == Ident::new(birb_std_lib::EQ_TRAIT)
+            && ck_comparable(cx, var_cx, &self_type, &mut HashSet::new()).is_ok();
+          if !structural {
+            return Err(e);
+          }
+        }
+        let mut self_vars = HashMap::with_capacity(1);
+        self_vars.insert(self_ident(), self_type);
+        FnInfo {
+          pub_: trait_info.pub_,
+          big_params: Vec::new(),
+          bounds: Vec::new(),
+          params: info
+            .params
+            .iter()
+            .map(|p| Param {
+              ident: p.ident.clone(),
+              type_: subst_kinded(&self_vars, p.type_.clone()),
+            })
+            .collect(),
+          ret_type: subst_kinded(&self_vars, info.ret_type.clone()),
+        }
+      } else if let Some((enum_name, enum_info, ctor)) = get_ctor(cx, name)? {
+        ck_visible(var_cx, enum_name, enum_info.pub_)?;
+        let types = match ctor {
+          CtorInfo::Positional(ts) => ts,
+          CtorInfo::Named(..) => return Err(Error::NamedCtor(name.clone())),
+        };
+        FnInfo {
+          pub_: enum_info.pub_,
           big_params: enum_info.params.clone(),
-          params: vec![Param {
-            ident: Ident::new("_"),
-            type_: type_.clone(),
-          }],
+          bounds: Vec::new(),
+          params: types
+            .iter()
+            .map(|t| Param {
+              ident: Ident::new("_"),
+              type_: t.clone(),
+            })
+            .collect(),
           ret_type: Kinded::Ident(
             enum_name.clone(),
             enum_info
@@ -405,23 +1682,44 @@ fn get_expr_type(cx: &Cx, var_cx: &VarCx, expr: &Expr) -> Result<ExprRes> {
               .map(|x| Kinded::Ident(x.ident.clone(), vec![]))
               .collect(),
           ),
-        })
-      }) {
-        info
+        }
       } else {
         return Err(Error::UndefinedIdentifier(name.clone()));
       };
-      if info.big_params.len() != big_args.len() {
-        return Err(Error::WrongNumArgs(
-          name.clone(),
-          info.big_params.len(),
-          big_args.len(),
-        ));
+      let big_vars = if big_args.is_empty() && !info.big_params.is_empty() {
+        if info.params.len() != args.len() {
+          return Err(Error::WrongNumArgs(
+            name.clone(),
+            info.params.len(),
+            args.len(),
+          ));
+        }
+        let (big_vars, got) = infer_big_args(cx, var_cx, name, &info, args, want)?;
+        got_args = got;
+        big_vars
+      } else {
+        if info.big_params.len() != big_args.len() {
+          return Err(Error::WrongNumArgs(
+            name.clone(),
+            info.big_params.len(),
+            big_args.len(),
+          ));
+        }
+        let mut big_vars = HashMap::with_capacity(big_args.len());
+        for (p, a) in info.big_params.iter().zip(big_args) {
+          big_vars.insert(p.ident.clone(), a.clone());
+        }
+        big_vars
+      };
+      for p in info.big_params.iter() {
+        ck_has_kind(cx, var_cx, &big_vars[&p.ident], p.type_.clone())?;
       }
-      let mut big_vars = HashMap::with_capacity(big_args.len());
-      for (p, a) in info.big_params.iter().zip(big_args) {
-        ck_has_kind(&cx, var_cx, a, p.type_.clone())?;
-        big_vars.insert(p.ident.clone(), a.clone());
+      for b in info.bounds.iter() {
+        ck_impl(cx, var_cx, &b.type_, &expand(cx, &big_vars[&b.ident]))?;
+      }
+      if is_intrinsic(&birb_std_lib::KEYED, name) {
+        let key_type = &big_vars[&info.big_params[0].ident];
+        ck_comparable(cx, var_cx, &expand(cx, key_type), &mut HashSet::new())?;
       }
       if info.params.len() != args.len() {
         return Err(Error::WrongNumArgs(
@@ -431,26 +1729,29 @@ fn get_expr_type(cx: &Cx, var_cx: &VarCx, expr: &Expr) -> Result<ExprRes> {
         ));
       }
       let mut effects = HashSet::new();
+      let mut got_args = got_args.into_iter();
       for (p, a) in info.params.iter().zip(args) {
         let want = subst_kinded(&big_vars, p.type_.clone());
-        let got = get_expr_type(cx, var_cx, a)?;
-        if want != got.typ {
-          return Err(Error::MismatchedTypes(want, got.typ));
-        }
+        let got = match got_args.next() {
+          Some(got) => got,
+          None => ck_expr(cx, var_cx, a, &want)?,
+        };
+        ck_type(cx, &want, &got.typ)?;
         effects.extend(got.effects);
       }
-      let ret_type = match subst_kinded(&big_vars, info.ret_type) {
+      let ret_type = subst_kinded(&big_vars, info.ret_type);
+      let ret_type = match expand(cx, &ret_type) {
         Kinded::Effectful(typ, eff) => {
           effects.extend(flatten(*eff));
           *typ
         }
-        other => other,
+        _ => ret_type,
       };
       Ok(ExprRes::effectful(ret_type, effects))
     }
     Expr::FieldGet(struct_, field) => {
       let type_ = get_expr_type(cx, var_cx, struct_)?;
-      let (name, args) = match type_.typ {
+      let (name, args) = match expand(cx, &type_.typ) {
         Kinded::Ident(name, args) => (name, args),
         _ => return Err(Error::NotStruct(field.clone())),
       };
@@ -475,27 +1776,176 @@ fn get_expr_type(cx: &Cx, var_cx: &VarCx, expr: &Expr) -> Result<ExprRes> {
       ))
     }
     Expr::MethodCall(..) => unreachable!("check method call"),
+    Expr::BinOp(..) => unreachable!("check bin op"),
+    Expr::Not(..) => unreachable!("check not"),
+    Expr::If(..) => unreachable!("check if"),
     Expr::Match(head, arms) => {
       let head_type = get_expr_type(cx, var_cx, head)?;
       let mut iter = arms.iter();
       // NOTE does not check exhaustiveness
-      let res_type = match iter.next() {
-        Some(arm) => get_arm_type(cx, var_cx.clone(), arm, &head_type.typ)?,
+      let first = match iter.next() {
+        Some(arm) => get_arm_type(cx, var_cx.clone(), arm, &head_type.typ, want)?,
         None => return Err(Error::EmptyMatch),
       };
+      let mut res_type = first.typ;
       let mut effects = head_type.effects;
+      effects.extend(first.effects);
       for arm in iter {
-        let got = get_arm_type(cx, var_cx.clone(), arm, &head_type.typ)?;
-        if res_type.typ != got.typ {
-          return Err(Error::MismatchedTypes(res_type.typ, got.typ));
-        }
+        let got = get_arm_type(cx, var_cx.clone(), arm, &head_type.typ, want)?;
+        res_type = join(cx, res_type, got.typ)?;
         effects.extend(got.effects);
       }
-      effects.extend(res_type.effects);
-      Ok(ExprRes::effectful(res_type.typ, effects))
+      Ok(ExprRes::effectful(res_type, effects))
+    }
+    Expr::Block(block) => get_block_type(cx, var_cx.clone(), block, want),
+    Expr::Return(e) => {
+      let want = match &var_cx.ret_type {
+        Some(x) => x,
+        None => return Err(Error::InvalidReturn),
+      };
+      let got = ck_expr(cx, var_cx, e, want)?;
+      Ok(ExprRes::effectful(Kinded::Never, got.effects))
+    }
+  }
+}
+
+/// Infers the big args of a call which has none written, from the types of the args, and from the
+/// type the call should have if it is known. Also returns the results of checking the args.
+fn infer_big_args(
+  cx: &Cx,
+  var_cx: &VarCx,
+  name: &Ident,
+  info: &FnInfo,
+  args: &[Expr],
+  want: Option<&Kinded>,
+) -> Result<(HashMap<Ident, Kinded>, Vec<ExprRes>)> {
+  let unknowns: HashSet<_> = info.big_params.iter().map(|p| p.ident.clone()).collect();
+  let mut solved = HashMap::with_capacity(unknowns.len());
+  if let Some(want) = want {
+    let ret_type = match expand(cx, &info.ret_type) {
+      Kinded::Effectful(typ, _) => *typ,
+      typ => typ,
+    };
+    // if this doesn't match, the mismatch is reported after checking the args.
+    unify_types(cx, &unknowns, &mut solved, &ret_type, want);
+  }
+  let mut got_args = Vec::with_capacity(args.len());
+  for (p, a) in info.params.iter().zip(args) {
+    let unsolved = unknowns
+      .iter()
+      .any(|u| !solved.contains_key(u) && mentions(&p.type_, u));
+    let got = if unsolved {
+      get_expr_type(cx, var_cx, a)?
+    } else {
+      ck_expr(cx, var_cx, a, &subst_kinded(&solved, p.type_.clone()))?
+    };
+    if !unify_types(cx, &unknowns, &mut solved, &p.type_, &got.typ) {
+      return Err(mismatch(
+        cx,
+        subst_kinded(&solved, p.type_.clone()),
+        got.typ,
+      ));
+    }
+    got_args.push(got);
+  }
+  if let Some(p) = info
+    .big_params
+    .iter()
+    .find(|p| !solved.contains_key(&p.ident))
+  {
+    return Err(Error::CannotInferBigArg(name.clone(), p.ident.clone()));
+  }
+  Ok((solved, got_args))
+}
+
+/// Matches the pattern, which may mention the unknowns, against the type, solving the unknowns.
+/// Returns whether they matched.
+fn unify_types(
+  cx: &Cx,
+  unknowns: &HashSet<Ident>,
+  solved: &mut HashMap<Ident, Kinded>,
+  pattern: &Kinded,
+  got: &Kinded,
+) -> bool {
+  match (expand(cx, pattern), expand(cx, got)) {
+    // `!` says nothing about the unknowns.
+    (_, Kinded::Never) => true,
+    (Kinded::Ident(name, args), got) if unknowns.contains(&name) => {
+      // the unknown may be applied to args, like `F[A]`, in which case it is solved with the head
+      // of the type, like `Result[Str]` for `Result[Str, Nat]`.
+      let (head, got_args) = if args.is_empty() {
+        (got, Vec::new())
+      } else {
+        match got {
+          Kinded::Ident(got_name, mut got_args) if got_args.len() >= args.len() => {
+            let rest = got_args.split_off(got_args.len() - args.len());
+            (Kinded::Ident(got_name, got_args), rest)
+          }
+          _ => return false,
+        }
+      };
+      let ok = match solved.get(&name) {
+        Some(prev) => is_compatible(prev, &head),
+        None => {
+          solved.insert(name, head);
+          true
+        }
+      };
+      ok && args
+        .iter()
+        .zip(got_args.iter())
+        .all(|(a, g)| unify_types(cx, unknowns, solved, a, g))
+    }
+    (Kinded::Ident(w, w_args), Kinded::Ident(g, g_args)) => {
+      w == g
+        && w_args.len() == g_args.len()
+        && w_args
+          .iter()
+          .zip(g_args.iter())
+          .all(|(w, g)| unify_types(cx, unknowns, solved, w, g))
+    }
+    (Kinded::Tuple(ws), Kinded::Tuple(gs)) | (Kinded::Set(ws), Kinded::Set(gs)) => {
+      ws.len() == gs.len()
+        && ws
+          .iter()
+          .zip(gs.iter())
+          .all(|(w, g)| unify_types(cx, unknowns, solved, w, g))
     }
-    Expr::Block(block) => get_block_type(cx, var_cx.clone(), block),
+    (Kinded::Arrow(w1, w2), Kinded::Arrow(g1, g2))
+    | (Kinded::Effectful(w1, w2), Kinded::Effectful(g1, g2)) => {
+      unify_types(cx, unknowns, solved, &w1, &g1) && unify_types(cx, unknowns, solved, &w2, &g2)
+    }
+    (pattern, got) => pattern == got,
+  }
+}
+
+/// Returns the enum name, enum info, and ctor info for the ctor, which may be qualified by the enum
+/// name, like `Option::none`. Errors if an unqualified ctor is in more than one enum.
+fn get_ctor<'a>(
+  cx: &'a Cx,
+  name: &Ident,
+) -> Result<Option<(&'a Ident, &'a EnumInfo, &'a CtorInfo)>> {
+  if let Some((qual, name)) = name.split() {
+    let ret = cx
+      .enums
+      .get_key_value(&qual)
+      .and_then(|(enum_name, info)| info.ctors.get(&name).map(|type_| (enum_name, info, type_)));
+    return Ok(ret);
   }
+  let mut found: Vec<_> = cx
+    .enums
+    .iter()
+    .filter_map(|(enum_name, info)| info.ctors.get(name).map(|type_| (enum_name, info, type_)))
+    .collect();
+  if found.len() > 1 {
+    let mut qualified: Vec<_> = found
+      .iter()
+      .map(|&(e, _, _)| Ident::qualified(e, name))
+      .collect();
+    qualified.sort();
+    return Err(Error::AmbiguousCtor(name.clone(), qualified));
+  }
+  Ok(found.pop())
 }
 
 fn subst_kinded(vars: &HashMap<Ident, Kinded>, kinded: Kinded) -> Kinded {
@@ -504,19 +1954,7 @@ fn subst_kinded(vars: &HashMap<Ident, Kinded>, kinded: Kinded) -> Kinded {
       let args: Vec<_> = args.into_iter().map(|a| subst_kinded(vars, a)).collect();
       match vars.get(&id) {
         None => Kinded::Ident(id, args),
-        Some(var_kinded) => {
-          if args.is_empty() {
-            var_kinded.clone()
-          } else {
-            match var_kinded {
-              Kinded::Ident(var_id, var_args) => {
-                assert!(var_args.is_empty());
-                Kinded::Ident(var_id.clone(), args)
-              }
-              _ => unreachable!(),
-            }
-          }
-        }
+        Some(var_kinded) => apply_kinded(var_kinded.clone(), args),
       }
     }
     Kinded::Tuple(ts) => Kinded::Tuple(ts.into_iter().map(|t| subst_kinded(vars, t)).collect()),
@@ -528,10 +1966,28 @@ fn subst_kinded(vars: &HashMap<Ident, Kinded>, kinded: Kinded) -> Kinded {
     Kinded::Effectful(t, e) => {
       Kinded::Effectful(subst_kinded(vars, *t).into(), subst_kinded(vars, *e).into())
     }
+    Kinded::Never => Kinded::Never,
+  }
+}
+
+/// Applies the type constructor, which may already be partially applied, like `Result[Str]`, to
+/// more args.
+fn apply_kinded(kinded: Kinded, args: Vec<Kinded>) -> Kinded {
+  if args.is_empty() {
+    return kinded;
+  }
+  match kinded {
+    Kinded::Ident(id, mut prev) => {
+      prev.extend(args);
+      Kinded::Ident(id, prev)
+    }
+    // only names may have arrow kinds.
+    _ => unreachable!(),
   }
 }
 
 fn match_pat(cx: &Cx, pat: &Pat, typ: &Kinded) -> Result<HashMap<Ident, Kinded>> {
+  let typ = &expand(cx, typ);
   match pat {
     Pat::Wildcard => Ok(HashMap::new()),
     Pat::String_(_) => {
@@ -567,7 +2023,113 @@ fn match_pat(cx: &Cx, pat: &Pat, typ: &Kinded) -> Result<HashMap<Ident, Kinded>>
       }
       Ok(ret)
     }
-    Pat::Ctor(ctor_name, pat) => {
+    Pat::List(pats, rest) => {
+      let elem_type = match typ {
+        Kinded::Ident(name, args) if *name == Ident::new(birb_std_lib::LIST) => &args[0],
+        _ => return Err(Error::InvalidPattern(typ.clone())),
+      };
+      let mut ret = HashMap::new();
+      for p in pats {
+        ret = match union_no_dupe(ret, match_pat(cx, p, elem_type)?) {
+          Ok(x) => x,
+          Err(id) => return Err(Error::DuplicateIdentifier(id)),
+        };
+      }
+      if let Some(rest) = rest {
+        ret = match union_no_dupe(ret, match_pat(cx, rest, typ)?) {
+          Ok(x) => x,
+          Err(id) => return Err(Error::DuplicateIdentifier(id)),
+        };
+      }
+      Ok(ret)
+    }
+    Pat::Struct(name, fields, rest) => {
+      let (type_name, args) = match typ {
+        Kinded::Ident(type_name, args) => (type_name, args),
+        _ => return Err(Error::InvalidPattern(typ.clone())),
+      };
+      // either a struct, or a ctor with named fields.
+      let (params, info_fields) = if type_name == name {
+        match cx.structs.get(name) {
+          Some(info) => (&info.params, &info.fields),
+          None => return Err(Error::InvalidPattern(typ.clone())),
+        }
+      } else {
+        let info = match cx.enums.get(type_name) {
+          Some(x) => x,
+          None => return Err(Error::InvalidPattern(typ.clone())),
+        };
+        if let Some((qual, _)) = name.split() {
+          if qual != *type_name {
+            return Err(Error::InvalidPattern(typ.clone()));
+          }
+        }
+        match info.ctors.get(&name.unqualified()) {
+          Some(CtorInfo::Named(fields)) => (&info.params, fields),
+          Some(CtorInfo::Positional(..)) => return Err(Error::NotStructName(name.clone())),
+          None => return Err(Error::InvalidPattern(typ.clone())),
+        }
+      };
+      assert_eq!(params.len(), args.len());
+      let big_vars: HashMap<_, _> = params
+        .iter()
+        .zip(args)
+        .map(|(p, a)| (p.ident.clone(), a.clone()))
+        .collect();
+      let mut fields_seen = HashSet::with_capacity(info_fields.len());
+      let mut ret = HashMap::new();
+      for f in fields {
+        let (x, pat) = match f {
+          Field::IdentAnd(x, pat) => (x, pat),
+          Field::Ident(..) => unreachable!("struct pattern field shorthand"),
+        };
+        let field_type = match info_fields.get(x) {
+          None => return Err(Error::NoSuchField(name.clone(), x.clone())),
+          Some(t) => subst_kinded(&big_vars, t.clone()),
+        };
+        if !fields_seen.insert(x) {
+          return Err(Error::DuplicateField(name.clone(), x.clone()));
+        }
+        ret = match union_no_dupe(ret, match_pat(cx, pat, &field_type)?) {
+          Ok(x) => x,
+          Err(id) => return Err(Error::DuplicateIdentifier(id)),
+        };
+      }
+      if !rest {
+        if let Some(x) = info_fields.keys().find(|x| !fields_seen.contains(x)) {
+          return Err(Error::MissingField(name.clone(), x.clone()));
+        }
+      }
+      Ok(ret)
+    }
+    // the cons and nil patterns of lists, like `cons(x, xs)` and `nil()`.
+    Pat::Ctor(ctor_name, pats)
+      if is_intrinsic(&[birb_std_lib::CONS, birb_std_lib::NIL], ctor_name)
+        && matches!(typ, Kinded::Ident(name, _) if *name == Ident::new(birb_std_lib::LIST)) =>
+    {
+      let types = match typ {
+        Kinded::Ident(_, args) if *ctor_name == Ident::new(birb_std_lib::CONS) => {
+          vec![args[0].clone(), typ.clone()]
+        }
+        _ => Vec::new(),
+      };
+      if types.len() != pats.len() {
+        return Err(Error::WrongNumArgs(
+          ctor_name.clone(),
+          types.len(),
+          pats.len(),
+        ));
+      }
+      let mut ret = HashMap::new();
+      for (pat, t) in pats.iter().zip(types.iter()) {
+        ret = match union_no_dupe(ret, match_pat(cx, pat, t)?) {
+          Ok(x) => x,
+          Err(id) => return Err(Error::DuplicateIdentifier(id)),
+        };
+      }
+      Ok(ret)
+    }
+    Pat::Ctor(ctor_name, pats) => {
       let (enum_name, args) = match typ {
         Kinded::Ident(enum_name, args) => (enum_name, args),
         _ => return Err(Error::InvalidPattern(typ.clone())),
@@ -576,10 +2138,23 @@ fn match_pat(cx: &Cx, pat: &Pat, typ: &Kinded) -> Result<HashMap<Ident, Kinded>>
         Some(x) => x,
         None => return Err(Error::InvalidPattern(typ.clone())),
       };
-      let ctor_type = match info.ctors.get(ctor_name) {
-        Some(x) => x,
+      if let Some((qual, _)) = ctor_name.split() {
+        if qual != *enum_name {
+          return Err(Error::InvalidPattern(typ.clone()));
+        }
+      }
+      let ctor_types = match info.ctors.get(&ctor_name.unqualified()) {
+        Some(CtorInfo::Positional(ts)) => ts,
+        Some(CtorInfo::Named(..)) => return Err(Error::NamedCtor(ctor_name.clone())),
         None => return Err(Error::InvalidPattern(typ.clone())),
       };
+      if ctor_types.len() != pats.len() {
+        return Err(Error::WrongNumArgs(
+          ctor_name.clone(),
+          ctor_types.len(),
+          pats.len(),
+        ));
+      }
       assert_eq!(info.params.len(), args.len());
       let big_vars: HashMap<_, _> = info
         .params
@@ -587,33 +2162,93 @@ fn match_pat(cx: &Cx, pat: &Pat, typ: &Kinded) -> Result<HashMap<Ident, Kinded>>
         .zip(args)
         .map(|(p, a)| (p.ident.clone(), a.clone()))
         .collect();
-      match_pat(cx, &**pat, &subst_kinded(&big_vars, ctor_type.clone()))
+      let mut ret = HashMap::new();
+      for (pat, t) in pats.iter().zip(ctor_types) {
+        ret = match union_no_dupe(
+          ret,
+          match_pat(cx, pat, &subst_kinded(&big_vars, t.clone()))?,
+        ) {
+          Ok(x) => x,
+          Err(id) => return Err(Error::DuplicateIdentifier(id)),
+        };
+      }
+      Ok(ret)
     }
     Pat::Ident(name) => {
       let mut ret = HashMap::new();
       ret.insert(name.clone(), typ.clone());
       Ok(ret)
     }
+    Pat::Or(pats) => {
+      let mut iter = pats.iter();
+      let ret = match_pat(cx, iter.next().unwrap(), typ)?;
+      for p in iter {
+        let mut other = match_pat(cx, p, typ)?;
+        for (name, t) in ret.iter() {
+          match other.remove(name) {
+            None => return Err(Error::InconsistentOrPat(name.clone())),
+            Some(other_t) => {
+              if *t != other_t {
+                return Err(mismatch(cx, t.clone(), other_t));
+              }
+            }
+          }
+        }
+        if let Some(name) = other.into_keys().next() {
+          return Err(Error::InconsistentOrPat(name));
+        }
+      }
+      Ok(ret)
+    }
+    Pat::At(name, pat) => {
+      let mut ret = match_pat(cx, pat, typ)?;
+      if ret.insert(name.clone(), typ.clone()).is_some() {
+        return Err(Error::DuplicateIdentifier(name.clone()));
+      }
+      Ok(ret)
+    }
   }
 }
 
-fn get_arm_type(cx: &Cx, mut var_cx: VarCx, arm: &Arm, typ: &Kinded) -> Result<ExprRes> {
+fn get_arm_type(
+  cx: &Cx,
+  mut var_cx: VarCx,
+  arm: &Arm,
+  typ: &Kinded,
+  want: Option<&Kinded>,
+) -> Result<ExprRes> {
+  // the vars of the pattern shadow any vars with the same names, including the params, until the
+  // end of the arm.
   var_cx.vars.extend(match_pat(cx, &arm.pat, typ)?);
-  get_block_type(cx, var_cx, &arm.block)
+  let mut effects = HashSet::new();
+  if let Some(guard) = &arm.guard {
+    effects = ck_expr(cx, &var_cx, guard, &bool_type())?.effects;
+  }
+  let mut ret = get_block_type(cx, var_cx, &arm.block, want)?;
+  ret.effects.extend(effects);
+  Ok(ret)
 }
 
-fn get_block_type(cx: &Cx, mut var_cx: VarCx, blk: &Block) -> Result<ExprRes> {
+fn get_block_type(
+  cx: &Cx,
+  mut var_cx: VarCx,
+  blk: &Block,
+  want: Option<&Kinded>,
+) -> Result<ExprRes> {
   let mut effects = HashSet::new();
   for stmt in blk.stmts.iter() {
     match stmt {
       Stmt::Let(pat, typ, expr) => {
-        let got = get_expr_type(cx, &var_cx, expr)?;
-        if let Some(typ) = typ {
-          if *typ != got.typ {
-            return Err(Error::MismatchedTypes(typ.clone(), got.typ));
+        let (typ, got) = match typ {
+          None => {
+            let got = get_expr_type(cx, &var_cx, expr)?;
+            (got.typ.clone(), got)
           }
-        }
-        var_cx.vars.extend(match_pat(cx, pat, &got.typ)?);
+          Some(typ) => (typ.clone(), ck_expr(cx, &var_cx, expr, typ)?),
+        };
+        // the vars of the pattern shadow any vars with the same names until the end of the block.
+        // a pattern may not bind a name twice, nor may a fn have two params with the same name.
+        var_cx.vars.extend(match_pat(cx, pat, &typ)?);
         effects.extend(got.effects);
       }
     }
@@ -621,13 +2256,104 @@ fn get_block_type(cx: &Cx, mut var_cx: VarCx, blk: &Block) -> Result<ExprRes> {
   match &blk.expr {
     None => Err(Error::NoExprForBlock),
     Some(e) => {
-      let mut got = get_expr_type(cx, &var_cx, e)?;
+      let mut got = expr_type(cx, &var_cx, e, want)?;
       got.effects.extend(effects);
       Ok(got)
     }
   }
 }
 
+/// Expands all the aliases in the type, which must have been checked to be well-kinded.
+fn expand(cx: &Cx, kinded: &Kinded) -> Kinded {
+  match kinded {
+    Kinded::Ident(name, args) => {
+      let args: Vec<_> = args.iter().map(|a| expand(cx, a)).collect();
+      match cx.aliases.get(name) {
+        None => Kinded::Ident(name.clone(), args),
+        Some(info) => {
+          let mut args = args.into_iter();
+          let big_vars: HashMap<_, _> = info
+            .params
+            .iter()
+            .zip(args.by_ref())
+            .map(|(p, a)| (p.ident.clone(), a))
+            .collect();
+          // any more args are for the type constructor the alias is for.
+          apply_kinded(subst_kinded(&big_vars, info.def.clone()), args.collect())
+        }
+      }
+    }
+    Kinded::Tuple(ts) => Kinded::Tuple(ts.iter().map(|t| expand(cx, t)).collect()),
+    Kinded::Set(es) => Kinded::Set(es.iter().map(|e| expand(cx, e)).collect()),
+    Kinded::Arrow(t1, t2) => Kinded::Arrow(expand(cx, t1).into(), expand(cx, t2).into()),
+    Kinded::Effectful(t, e) => Kinded::Effectful(expand(cx, t).into(), expand(cx, e).into()),
+    Kinded::Never => Kinded::Never,
+  }
+}
+
+/// Returns whether the big identifier appears anywhere in the kinded.
+fn mentions(kinded: &Kinded, name: &Ident) -> bool {
+  match kinded {
+    Kinded::Ident(bi, args) => bi == name || args.iter().any(|a| mentions(a, name)),
+    Kinded::Tuple(ks) | Kinded::Set(ks) => ks.iter().any(|k| mentions(k, name)),
+    Kinded::Arrow(k1, k2) | Kinded::Effectful(k1, k2) => mentions(k1, name) || mentions(k2, name),
+    Kinded::Never => false,
+  }
+}
+
+/// Checks that a value of type `got` may be used where one of type `want` is expected.
+fn ck_type(cx: &Cx, want: &Kinded, got: &Kinded) -> Result<()> {
+  if is_compatible(&expand(cx, want), &expand(cx, got)) {
+    Ok(())
+  } else {
+    Err(mismatch(cx, want.clone(), got.clone()))
+  }
+}
+
+/// Returns the error for when we expected `want` but got `got`.
+fn mismatch(cx: &Cx, want: Kinded, got: Kinded) -> Error {
+  let want_exp = expand(cx, &want);
+  let got_exp = expand(cx, &got);
+  if want == want_exp && got == got_exp {
+    Error::MismatchedTypes(want, got)
+  } else {
+    Error::MismatchedAliasedTypes((want, want_exp).into(), (got, got_exp).into())
+  }
+}
+
+/// Returns whether a value of type `got` may be used where one of type `want` is expected. This is
+/// equality, except that `!` may be used as any type. The types must have no aliases.
+fn is_compatible(want: &Kinded, got: &Kinded) -> bool {
+  match (want, got) {
+    (_, Kinded::Never) => true,
+    (Kinded::Ident(w, w_args), Kinded::Ident(g, g_args)) => {
+      w == g
+        && w_args.len() == g_args.len()
+        && w_args.iter().zip(g_args).all(|(w, g)| is_compatible(w, g))
+    }
+    (Kinded::Tuple(ws), Kinded::Tuple(gs)) => {
+      ws.len() == gs.len() && ws.iter().zip(gs).all(|(w, g)| is_compatible(w, g))
+    }
+    _ => want == got,
+  }
+}
+
+/// Returns the type of something which may be either of the given types, like the arms of a match.
+fn join(cx: &Cx, a: Kinded, b: Kinded) -> Result<Kinded> {
+  let (a_exp, b_exp) = (expand(cx, &a), expand(cx, &b));
+  if is_compatible(&a_exp, &b_exp) {
+    Ok(a)
+  } else if is_compatible(&b_exp, &a_exp) {
+    Ok(b)
+  } else {
+    Err(mismatch(cx, a, b))
+  }
+}
+
+fn is_intrinsic(names: &[&str], name: &Ident) -> bool {
+  names.iter().any(|&x| *name == Ident::new(x))
+}
+
 fn str_type() -> Kinded {
   Kinded::Ident(Ident::new(birb_std_lib::STR), vec![])
 }
@@ -640,15 +2366,29 @@ fn bool_type() -> Kinded {
   Kinded::Ident(Ident::new(birb_std_lib::BOOL), vec![])
 }
 
+fn list_type(elem: Kinded) -> Kinded {
+  Kinded::Ident(Ident::new(birb_std_lib::LIST), vec![elem])
+}
+
 fn ret_ident() -> Ident {
   Ident::new("ret")
 }
 
+fn self_ident() -> Ident {
+  Ident::new("Self")
+}
+
+fn self_type() -> Kinded {
+  Kinded::Ident(self_ident(), vec![])
+}
+
 fn flatten(ef: Kinded) -> HashSet<Kinded> {
   match ef {
     Kinded::Ident(..) => std::iter::once(ef).collect(),
     Kinded::Set(efs) => efs.into_iter().flat_map(flatten).collect(),
-    Kinded::Tuple(..) | Kinded::Arrow(..) | Kinded::Effectful(..) => unreachable!(),
+    Kinded::Tuple(..) | Kinded::Arrow(..) | Kinded::Effectful(..) | Kinded::Never => {
+      unreachable!()
+    }
   }
 }
 
@@ -2,89 +2,123 @@
 
 #![allow(missing_docs)]
 
-use crate::cst::{Block, EnumDefn, Expr, FnDefn, Kinded, Param, TopDefn};
+use crate::cst::TopDefn;
 use crate::ident::Ident;
 use std::collections::HashSet;
 
-/// The pre-defined top definitions. This isn't the prettiest way to do this.
+/// The pre-defined top definitions, from the prelude.
 pub fn top_defns() -> Vec<TopDefn> {
-  vec![
-    TopDefn::Enum(EnumDefn {
-      name: Ident::new(BOOL),
-      params: vec![],
-      ctors: vec![
-        Param {
-          ident: Ident::new("true"),
-          type_: Kinded::Tuple(vec![]),
-        },
-        Param {
-          ident: Ident::new("false"),
-          type_: Kinded::Tuple(vec![]),
-        },
-      ],
-    }),
-    TopDefn::Enum(EnumDefn {
-      name: Ident::new(NAT),
-      params: vec![],
-      ctors: vec![],
-    }),
-    TopDefn::Enum(EnumDefn {
-      name: Ident::new(STR),
-      params: vec![],
-      ctors: vec![],
-    }),
-    bin_op(ADD, NAT, NAT, NAT, Expr::Number(0)),
-    bin_op(SUB, NAT, NAT, NAT, Expr::Number(0)),
-    bin_op(MUL, NAT, NAT, NAT, Expr::Number(0)),
-    bin_op(DIV, NAT, NAT, NAT, Expr::Number(0)),
-    bin_op(EQ, NAT, NAT, BOOL, fake_bool()),
-    bin_op(LT, NAT, NAT, BOOL, fake_bool()),
-    bin_op(GT, NAT, NAT, BOOL, fake_bool()),
-    bin_op(AND, BOOL, BOOL, BOOL, fake_bool()),
-    bin_op(OR, BOOL, BOOL, BOOL, fake_bool()),
-  ]
+  let ts = crate::lex::get(PRELUDE.as_bytes()).expect("lex prelude");
+  crate::parse::get(&ts).expect("parse prelude")
 }
 
-fn fake_bool() -> Expr {
-  Expr::FnCall(Ident::new("true"), vec![], vec![Expr::Tuple(vec![])])
-}
+/// The source of the prelude.
+pub const PRELUDE: &str = include_str!("prelude.birb");
 
-fn bin_op(name: &str, lhs_type: &str, rhs_type: &str, ret_type: &str, ret_val: Expr) -> TopDefn {
-  TopDefn::Fn_(Box::new(FnDefn {
-    name: Ident::new(name),
-    big_params: vec![],
-    params: vec![
-      Param {
-        ident: Ident::new("lhs"),
-        type_: Kinded::Ident(Ident::new(lhs_type), vec![]),
-      },
-      Param {
-        ident: Ident::new("rhs"),
-        type_: Kinded::Ident(Ident::new(rhs_type), vec![]),
-      },
-    ],
-    ret_type: Kinded::Ident(Ident::new(ret_type), vec![]),
-    requires: None,
-    ensures: None,
-    body: Block {
-      stmts: vec![],
-      expr: Some(ret_val),
-    },
-  }))
-}
+/// The types which are implemented natively.
+pub const INTRINSIC_TYPES: [&str; 5] = [NAT, STR, LIST, MAP, SET];
+
+/// The functions which are implemented natively.
+pub const INTRINSIC_FNS: [&str; 25] = [
+  EQ_ANY,
+  CONCAT,
+  ADD,
+  SUB,
+  MUL,
+  DIV,
+  NIL,
+  CONS,
+  LEN,
+  APPEND,
+  REVERSE,
+  NTH,
+  MAP_NEW,
+  MAP_INSERT,
+  MAP_REMOVE,
+  MAP_CONTAINS,
+  MAP_GET,
+  MAP_SIZE,
+  MAP_ENTRIES,
+  SET_NEW,
+  SET_INSERT,
+  SET_REMOVE,
+  SET_CONTAINS,
+  SET_SIZE,
+  SET_TO_LIST,
+];
+
+/// The impls whose functions are implemented natively, as (trait, type) pairs.
+pub const INTRINSIC_IMPLS: [(&str, &str); 7] = [
+  (EQ_TRAIT, NAT),
+  (ORD_TRAIT, NAT),
+  (EQ_TRAIT, STR),
+  (ORD_TRAIT, STR),
+  (EQ_TRAIT, BOOL),
+  (SHOW_TRAIT, NAT),
+  (SHOW_TRAIT, STR),
+];
+
+/// The functions whose first type argument must be comparable, since its values are compared
+/// structurally or used as keys in a Map or Set.
+pub const KEYED: [&str; 14] = [
+  EQ_ANY,
+  MAP_NEW,
+  MAP_INSERT,
+  MAP_REMOVE,
+  MAP_CONTAINS,
+  MAP_GET,
+  MAP_SIZE,
+  MAP_ENTRIES,
+  SET_NEW,
+  SET_INSERT,
+  SET_REMOVE,
+  SET_CONTAINS,
+  SET_SIZE,
+  SET_TO_LIST,
+];
 
 pub const BOOL: &str = "Bool";
+pub const EXIT: &str = "Exit";
+pub const SUCCESS: &str = "success";
+pub const FAILURE: &str = "failure";
 pub const NAT: &str = "Nat";
 pub const STR: &str = "Str";
+pub const LIST: &str = "List";
+pub const MAP: &str = "Map";
+pub const SET: &str = "Set";
 pub const ADD: &str = "add";
 pub const SUB: &str = "sub";
 pub const MUL: &str = "mul";
 pub const DIV: &str = "div";
+pub const NOT: &str = "not";
+pub const EQ_TRAIT: &str = "Eq";
+pub const ORD_TRAIT: &str = "Ord";
+pub const SHOW_TRAIT: &str = "Show";
 pub const EQ: &str = "eq";
+pub const EQ_ANY: &str = "eq_any";
 pub const LT: &str = "lt";
 pub const GT: &str = "gt";
-pub const AND: &str = "and";
-pub const OR: &str = "or";
+pub const SHOW: &str = "show";
+pub const CONCAT: &str = "concat";
+pub const NIL: &str = "nil";
+pub const CONS: &str = "cons";
+pub const LEN: &str = "len";
+pub const APPEND: &str = "append";
+pub const REVERSE: &str = "reverse";
+pub const NTH: &str = "nth";
+pub const MAP_NEW: &str = "map_new";
+pub const MAP_INSERT: &str = "map_insert";
+pub const MAP_REMOVE: &str = "map_remove";
+pub const MAP_CONTAINS: &str = "map_contains";
+pub const MAP_GET: &str = "map_get";
+pub const MAP_SIZE: &str = "map_size";
+pub const MAP_ENTRIES: &str = "map_entries";
+pub const SET_NEW: &str = "set_new";
+pub const SET_INSERT: &str = "set_insert";
+pub const SET_REMOVE: &str = "set_remove";
+pub const SET_CONTAINS: &str = "set_contains";
+pub const SET_SIZE: &str = "set_size";
+pub const SET_TO_LIST: &str = "set_to_list";
 
 /// The pre-defined effects.
 pub fn effects() -> HashSet<Ident> {
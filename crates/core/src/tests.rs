@@ -16,25 +16,29 @@ fn simple() {
     vec![
       TopDefn::Struct(StructDefn {
         name: Ident::new("Unit"),
+        pub_: false,
         params: vec![],
         fields: vec![]
       }),
       TopDefn::Enum(EnumDefn {
         name: Ident::new("Void"),
+        pub_: false,
         params: vec![],
         ctors: vec![],
       }),
       TopDefn::Fn_(Box::new(FnDefn {
         name: Ident::new("main"),
+        pub_: false,
         big_params: vec![],
+        bounds: vec![],
         params: vec![],
         ret_type: Kinded::Ident(Ident::new("Str"), vec![]),
         requires: None,
         ensures: None,
-        body: Block {
+        body: Some(Block {
           stmts: vec![],
           expr: Some(Expr::String_(String::from("hello")))
-        },
+        }),
       })),
     ]
   );
@@ -47,9 +51,10 @@ fn call() {
     vec![
       TopDefn::Struct(StructDefn {
         name: Ident::new("Guy"),
+        pub_: false,
         params: vec![Param {
           ident: Ident::new("T"),
-          type_: Kind::Type,
+          type_: Some(Kind::Type),
         }],
         fields: vec![Param {
           ident: Ident::new("x"),
@@ -58,20 +63,22 @@ fn call() {
       }),
       TopDefn::Fn_(Box::new(FnDefn {
         name: Ident::new("call"),
+        pub_: false,
         big_params: vec![
           Param {
             ident: Ident::new("T"),
-            type_: Kind::Type,
+            type_: Some(Kind::Type),
           },
           Param {
             ident: Ident::new("U"),
-            type_: Kind::Type,
+            type_: Some(Kind::Type),
           },
           Param {
             ident: Ident::new("E"),
-            type_: Kind::Effect,
+            type_: Some(Kind::Effect),
           },
         ],
+        bounds: vec![],
         params: vec![
           Param {
             ident: Ident::new("f"),
@@ -95,7 +102,7 @@ fn call() {
         ),
         requires: Some(Expr::Ident(Ident::new("true"))),
         ensures: Some(Expr::Ident(Ident::new("true"))),
-        body: Block {
+        body: Some(Block {
           stmts: vec![
             Stmt::Let(
               Pat::Wildcard,
@@ -104,6 +111,7 @@ fn call() {
                 Ident::new("Guy"),
                 vec![Kinded::Ident(Ident::new("T"), vec![])],
                 vec![Field::Ident(Ident::new("x"))],
+                None,
               )
             ),
             Stmt::Let(
@@ -113,6 +121,7 @@ fn call() {
                 Expr::Tuple(vec![]).into(),
                 vec![Arm {
                   pat: Pat::Tuple(vec![]),
+                  guard: None,
                   block: Block {
                     stmts: vec![],
                     expr: Some(Expr::Tuple(vec![]))
@@ -138,8 +147,7 @@ fn call() {
             vec![],
             vec![],
           )),
-        }
-        .into(),
+        }),
       }))
     ]
   );
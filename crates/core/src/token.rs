@@ -8,32 +8,53 @@ use std::fmt;
 #[allow(missing_docs)]
 pub enum Token {
   // punctuation
+  AndAnd,
   Arrow,
+  At,
+  Bang,
+  BangEqual,
   Bar,
+  BarBar,
   Colon,
   ColonColon,
   Comma,
   Dot,
+  DotDot,
   Equal,
+  EqualEqual,
+  Gt,
+  GtEqual,
   LCurly,
   LRound,
   LSquare,
+  Lt,
+  LtEqual,
+  Minus,
   Plus,
   RCurly,
   RRound,
   RSquare,
   Semi,
+  Slash,
+  Star,
   Underscore,
   // reserved words
   Affects,
+  Else,
   Ensures,
   Enum,
   Fn_,
+  For,
+  If,
+  Impl,
+  Import,
   Let,
   Match,
+  Pub,
   Requires,
   Return,
   Struct,
+  Trait,
   Type,
   // other
   BigIdent(Ident),
@@ -47,32 +68,53 @@ impl Token {
   pub fn desc(&self) -> &'static str {
     match self {
       // punctuation
+      Self::AndAnd => "&&",
       Self::Arrow => "->",
+      Self::At => "@",
+      Self::Bang => "!",
+      Self::BangEqual => "!=",
       Self::Bar => "|",
+      Self::BarBar => "||",
       Self::Colon => ":",
       Self::ColonColon => "::",
       Self::Comma => ",",
       Self::Dot => ".",
+      Self::DotDot => "..",
       Self::Equal => "=",
+      Self::EqualEqual => "==",
+      Self::Gt => ">",
+      Self::GtEqual => ">=",
       Self::LCurly => "{",
       Self::LRound => "(",
       Self::LSquare => "[",
+      Self::Lt => "<",
+      Self::LtEqual => "<=",
+      Self::Minus => "-",
       Self::Plus => "+",
       Self::RCurly => "}",
       Self::RRound => ")",
       Self::RSquare => "]",
       Self::Semi => ";",
+      Self::Slash => "/",
+      Self::Star => "*",
       Self::Underscore => "_",
       // reserved words
       Self::Affects => "affects",
+      Self::Else => "else",
       Self::Ensures => "ensures",
       Self::Enum => "enum",
       Self::Fn_ => "fn",
+      Self::For => "for",
+      Self::If => "if",
+      Self::Impl => "impl",
+      Self::Import => "import",
       Self::Let => "let",
       Self::Match => "match",
+      Self::Pub => "pub",
       Self::Requires => "requires",
       Self::Return => "return",
       Self::Struct => "struct",
+      Self::Trait => "trait",
       Self::Type => "type",
       // other
       Self::BigIdent(..) => "a big identifier",
@@ -98,10 +140,17 @@ impl fmt::Display for Token {
 // these should be sorted longest first, then alphabetically
 
 /// Tokens composed of punctuation.
-pub const PUNCT: [(&[u8], Token); 16] = [
+pub const PUNCT: [(&[u8], Token); 30] = [
   // 2
+  (b"!=", Token::BangEqual),
+  (b"&&", Token::AndAnd),
   (b"->", Token::Arrow),
+  (b"..", Token::DotDot),
   (b"::", Token::ColonColon),
+  (b"<=", Token::LtEqual),
+  (b"==", Token::EqualEqual),
+  (b">=", Token::GtEqual),
+  (b"||", Token::BarBar),
   // 1
   (b"_", Token::Underscore),
   (b",", Token::Comma),
@@ -117,25 +166,39 @@ pub const PUNCT: [(&[u8], Token); 16] = [
   (b"+", Token::Plus),
   (b"=", Token::Equal),
   (b"|", Token::Bar),
+  (b"@", Token::At),
+  (b"!", Token::Bang),
+  (b"*", Token::Star),
+  (b"-", Token::Minus),
+  (b"/", Token::Slash),
+  (b"<", Token::Lt),
+  (b">", Token::Gt),
 ];
 
 /// Reserved words.
-pub const WORDS: [(&[u8], Token); 10] = [
+pub const WORDS: [(&[u8], Token); 17] = [
   // 8
   (b"requires", Token::Requires),
   // 7
   (b"affects", Token::Affects),
   (b"ensures", Token::Ensures),
   // 6
+  (b"import", Token::Import),
   (b"return", Token::Return),
   (b"struct", Token::Struct),
   // 5
   (b"match", Token::Match),
+  (b"trait", Token::Trait),
   // 4
+  (b"else", Token::Else),
   (b"enum", Token::Enum),
+  (b"impl", Token::Impl),
   (b"type", Token::Type),
   // 3
+  (b"for", Token::For),
   (b"let", Token::Let),
+  (b"pub", Token::Pub),
   // 2
   (b"fn", Token::Fn_),
+  (b"if", Token::If),
 ];
@@ -3,6 +3,7 @@ use std::fmt;
 pub struct SliceDisplay<'a, T> {
   left: &'static str,
   content: &'a [T],
+  sep: &'static str,
   right: &'static str,
 }
 
@@ -11,9 +12,14 @@ impl<'a, T> SliceDisplay<'a, T> {
     Self {
       left,
       content,
+      sep: ", ",
       right,
     }
   }
+
+  pub fn sep(self, sep: &'static str) -> Self {
+    Self { sep, ..self }
+  }
 }
 
 impl<'a, T> fmt::Display for SliceDisplay<'a, T>
@@ -27,7 +33,7 @@ where
       write!(f, "{}", x)?;
     }
     for x in iter {
-      write!(f, ", {}", x)?;
+      write!(f, "{}{}", self.sep, x)?;
     }
     write!(f, "{}", self.right)
   }
@@ -0,0 +1,132 @@
+//! Warnings, which unlike errors do not stop the program from running.
+
+use crate::ident::Ident;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A warning.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Warning {
+  /// A struct or enum with no finite values, which can never be constructed.
+  Uninhabited(Ident),
+  /// A let binding or param of the fn which is never used.
+  UnusedVar(Ident, Ident),
+  /// A big param of the fn which is never used.
+  UnusedBigParam(Ident, Ident),
+  /// A fn which is not `pub` and never called from main or a `pub` fn.
+  UnusedFn(Ident),
+  /// A var of the fn which is bound while another var with the same name is in scope.
+  Shadowed(Ident, Ident),
+  /// An imported module whose names are never used by the importing module.
+  UnusedImport(Ident),
+}
+
+impl Warning {
+  /// Returns the lint which produced this warning.
+  pub fn lint(&self) -> Lint {
+    match self {
+      Self::Uninhabited(..) => Lint::Uninhabited,
+      Self::UnusedVar(..) => Lint::UnusedVar,
+      Self::UnusedBigParam(..) => Lint::UnusedBigParam,
+      Self::UnusedFn(..) => Lint::UnusedFn,
+      Self::Shadowed(..) => Lint::Shadowing,
+      Self::UnusedImport(..) => Lint::UnusedImport,
+    }
+  }
+}
+
+impl fmt::Display for Warning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Uninhabited(id) => write!(
+        f,
+        "{} has no finite values, so it can never be constructed",
+        id
+      ),
+      Self::UnusedVar(func, id) => write!(f, "unused variable {} in {}", id, func),
+      Self::UnusedBigParam(func, id) => write!(f, "unused big param {} of {}", id, func),
+      Self::UnusedFn(id) => write!(f, "unused fn {}", id),
+      Self::Shadowed(func, id) => write!(f, "{} in {} shadows an earlier {}", id, func, id),
+      Self::UnusedImport(id) => write!(f, "unused import {}", id),
+    }
+  }
+}
+
+/// A kind of warning, which may be allowed or denied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+  /// Warns about types with no finite values.
+  Uninhabited,
+  /// Warns about unused let bindings and params.
+  UnusedVar,
+  /// Warns about unused big params.
+  UnusedBigParam,
+  /// Warns about unused fns.
+  UnusedFn,
+  /// Warns about vars which shadow other vars. Allowed unless asked for, since shadowing is often
+  /// on purpose.
+  Shadowing,
+  /// Warns about unused imports.
+  UnusedImport,
+}
+
+impl Lint {
+  /// All the lints.
+  pub const ALL: [Lint; 6] = [
+    Lint::Uninhabited,
+    Lint::UnusedVar,
+    Lint::UnusedBigParam,
+    Lint::UnusedFn,
+    Lint::Shadowing,
+    Lint::UnusedImport,
+  ];
+
+  /// Returns the name of the lint, like `unused-var`.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Uninhabited => "uninhabited",
+      Self::UnusedVar => "unused-var",
+      Self::UnusedBigParam => "unused-big-param",
+      Self::UnusedFn => "unused-fn",
+      Self::Shadowing => "shadowing",
+      Self::UnusedImport => "unused-import",
+    }
+  }
+
+  /// Returns the level of the lint when none is given.
+  pub fn default_level(&self) -> Level {
+    match self {
+      Self::Shadowing => Level::Allow,
+      Self::Uninhabited
+      | Self::UnusedVar
+      | Self::UnusedBigParam
+      | Self::UnusedFn
+      | Self::UnusedImport => Level::Warn,
+    }
+  }
+
+  /// Returns the lint with the name, if any.
+  pub fn from_name(name: &str) -> Option<Lint> {
+    Self::ALL.into_iter().find(|l| l.name() == name)
+  }
+}
+
+impl fmt::Display for Lint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+/// What to do with the warnings of a lint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+  /// Drop the warnings.
+  Allow,
+  /// Report the warnings.
+  Warn,
+  /// Report the warnings as errors.
+  Deny,
+}
+
+/// The levels of the lints. Lints not in the map have their default level.
+pub type Levels = HashMap<Lint, Level>;
@@ -1,7 +1,14 @@
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub fn get(inp: &str) -> String {
-  match birb_core::get(inp.as_bytes()) {
-    Ok(v) => format!("main() = {}", v),
+  match birb_core::get(inp.as_bytes(), &[]) {
+    Ok((warnings, v)) => {
+      let mut ret = String::new();
+      for w in warnings {
+        ret.push_str(&format!("warning: {}\n", w));
+      }
+      ret.push_str(&format!("main() = {}", v));
+      ret
+    }
     Err(e) => format!("error: {}", e),
   }
 }
@@ -24,6 +24,10 @@ const abstractSyntax: AbstractSyntax = [
       { math: String.raw`[ L_i : \tau_i ]`, text: "labeled sum" },
       { math: String.raw`\tau_1 \rightarrow \tau_2!E`, text: "arrow" },
       { math: String.raw`\forall (t : \kappa) \ \tau`, text: "universal" },
+      {
+        math: String.raw`\forall (t : \kappa) \ T(t) \Rightarrow \tau`,
+        text: "bounded universal",
+      },
       { math: String.raw`\lambda (t : \kappa) \ \tau`, text: "function" },
       { math: String.raw`\tau_1[\tau_2]`, text: "application" },
     ],
@@ -56,6 +60,7 @@ const abstractSyntax: AbstractSyntax = [
       { math: String.raw`L \cdot v`, text: "labeled sum" },
       { math: String.raw`\lambda (x : \tau) \ c`, text: "function" },
       { math: String.raw`\Lambda (t : \kappa) \ v`, text: "big function" },
+      { math: String.raw`T[\tau] \cdot f`, text: "trait fn of an impl" },
     ],
   },
   {
@@ -75,6 +80,8 @@ const abstractSyntax: AbstractSyntax = [
       { math: String.raw`v_1(v_2)`, text: "value application" },
       { math: String.raw`v[\tau]`, text: "type application" },
       { math: String.raw`v[E]`, text: "effect application" },
+      { math: String.raw`\textsf{return} \ v`, text: "early return" },
+      { math: String.raw`\textsf{assert} \ v`, text: "contract check" },
     ],
   },
 ];
@@ -7,13 +7,41 @@ const syntax: Grammar = verify(imported, exported, [
   { name: "program", def: [e, a(n("top-defn"), n("program"))] },
   {
     name: "top-defn",
+    def: [
+      a(t("import"), n("ident"), n("import-tl"), t(";")),
+      a(n("pub-opt"), n("defn")),
+      a(
+        t("impl"),
+        n("bounded-big-param-list-opt"),
+        n("big-name"),
+        t("for"),
+        n("kinded"),
+        t("{"),
+        n("fn-list"),
+        t("}"),
+      ),
+    ],
+  },
+  { name: "import-tl", def: [e, a(t("::"), n("ident"), n("import-tl"))] },
+  { name: "pub-opt", def: [e, t("pub")] },
+  {
+    name: "defn",
     def: [
       a(t("struct"), n("big-ident"), n("big-param-list-opt"), t("{"), n("param-list"), t("}")),
       a(t("enum"), n("big-ident"), n("big-param-list-opt"), t("{"), n("ctor-list"), t("}")),
+      a(t("type"), n("big-ident"), n("big-param-list-opt"), n("type-def-opt"), t(";")),
+      a(t("fn"), n("fn-tl")),
+      a(t("trait"), n("big-ident"), t("{"), n("fn-list"), t("}")),
+    ],
+  },
+  { name: "type-def-opt", def: [e, a(t("="), n("kinded"))] },
+  { name: "fn-list", def: [e, a(t("fn"), n("fn-tl"), n("fn-list"))] },
+  {
+    name: "fn-tl",
+    def: [
       a(
-        t("fn"),
         n("ident"),
-        n("big-param-list-opt"),
+        n("bounded-big-param-list-opt"),
         t("("),
         n("param-list"),
         t(")"),
@@ -21,10 +49,11 @@ const syntax: Grammar = verify(imported, exported, [
         n("kinded"),
         n("requires-clause"),
         n("ensures-clause"),
-        n("block"),
+        n("fn-body"),
       ),
     ],
   },
+  { name: "fn-body", def: [t(";"), n("block")] },
   {
     name: "big-param-list-opt",
     def: [e, a(t("["), n("big-param-list"), t("]"))],
@@ -35,8 +64,29 @@ const syntax: Grammar = verify(imported, exported, [
   },
   {
     name: "big-param",
-    def: [a(n("big-ident"), t(":"), n("kind"))],
+    def: [n("big-ident"), a(n("big-ident"), t(":"), n("kind"))],
   },
+  {
+    name: "bounded-big-param-list-opt",
+    def: [e, a(t("["), n("bounded-big-param-list"), t("]"))],
+  },
+  {
+    name: "bounded-big-param-list",
+    def: [
+      e,
+      n("bounded-big-param"),
+      a(n("bounded-big-param"), t(","), n("bounded-big-param-list")),
+    ],
+  },
+  {
+    name: "bounded-big-param",
+    def: [
+      n("big-ident"),
+      a(n("big-ident"), t(":"), n("kind"), n("bound-list")),
+      a(n("big-ident"), t(":"), n("big-name"), n("bound-list")),
+    ],
+  },
+  { name: "bound-list", def: [e, a(t("+"), n("big-name"), n("bound-list"))] },
   { name: "kind", def: [a(n("kind-hd"), n("kind-arrow"))] },
   {
     name: "kind-hd",
@@ -57,7 +107,7 @@ const syntax: Grammar = verify(imported, exported, [
   {
     name: "kinded-hd",
     def: [
-      a(n("big-ident"), n("kinded-args-opt")),
+      a(n("big-name"), n("kinded-args-opt")),
       a(t("("), n("kinded-list"), t(")")),
       a(t("{"), n("kinded-list"), t("}")),
     ],
@@ -74,13 +124,21 @@ const syntax: Grammar = verify(imported, exported, [
     name: "kinded-list",
     def: [e, n("kinded"), a(n("kinded"), t(","), n("kinded-list"))],
   },
+  { name: "name", def: [a(n("path"), n("name-part"))] },
+  { name: "big-name", def: [a(n("path"), n("big-ident"))] },
+  { name: "path", def: [e, a(n("name-part"), t("::"), n("path"))] },
+  { name: "name-part", def: [n("ident"), n("big-ident")] },
   {
     name: "ctor-list",
     def: [e, n("ctor"), a(n("ctor"), t(","), n("ctor-list"))],
   },
   {
     name: "ctor",
-    def: [a(n("ident"), t("("), n("kinded"), t(")"))],
+    def: [
+      n("ident"),
+      a(n("ident"), t("("), n("kinded-list"), t(")")),
+      a(n("ident"), t("{"), n("param-list"), t("}")),
+    ],
   },
   {
     name: "param-list",
@@ -102,41 +160,76 @@ const syntax: Grammar = verify(imported, exported, [
     name: "type-annotation",
     def: [e, a(t(":"), n("kinded"))],
   },
+  { name: "pat", def: [a(n("pat-at"), n("pat-or-tl"))] },
+  { name: "pat-or-tl", def: [e, a(t("|"), n("pat-at"), n("pat-or-tl"))] },
+  { name: "pat-at", def: [n("pat-hd"), a(n("ident"), t("@"), n("pat-at"))] },
   {
-    name: "pat",
+    name: "pat-hd",
     def: [
       t("_"),
       n("string"),
       n("number"),
       a(t("("), n("pat-list"), t(")")),
-      a(n("ident"), t("("), n("pat"), t(")")),
-      n("ident"),
+      a(t("["), n("pat-list"), n("pat-rest-opt"), t("]")),
+      a(n("name"), t("{"), n("field-pat-list"), n("dot-dot-opt"), t("}")),
+      a(n("name"), t("("), n("pat-list"), t(")")),
+      n("name"),
     ],
   },
   { name: "pat-list", def: [e, n("pat"), a(n("pat"), t(","), n("pat-list"))] },
+  { name: "pat-rest-opt", def: [e, a(t(".."), n("pat"))] },
+  { name: "dot-dot-opt", def: [e, t("..")] },
   {
-    name: "expr",
-    def: [a(n("expr-hd"), n("expr-tl-list"))],
+    name: "field-pat-list",
+    def: [e, n("field-pat"), a(n("field-pat"), t(","), n("field-pat-list"))],
   },
+  { name: "field-pat", def: [n("ident"), a(n("ident"), t(":"), n("pat"))] },
+  { name: "expr", def: [a(n("expr-and"), n("expr-or-tl"))] },
+  { name: "expr-or-tl", def: [e, a(t("||"), n("expr-and"), n("expr-or-tl"))] },
+  { name: "expr-and", def: [a(n("expr-cmp"), n("expr-and-tl"))] },
+  { name: "expr-and-tl", def: [e, a(t("&&"), n("expr-cmp"), n("expr-and-tl"))] },
+  { name: "expr-cmp", def: [a(n("expr-add"), n("expr-cmp-tl"))] },
+  { name: "expr-cmp-tl", def: [e, a(n("cmp-op"), n("expr-add"), n("expr-cmp-tl"))] },
+  { name: "cmp-op", def: [t("=="), t("!="), t("<"), t(">"), t("<="), t(">=")] },
+  { name: "expr-add", def: [a(n("expr-mul"), n("expr-add-tl"))] },
+  { name: "expr-add-tl", def: [e, a(n("add-op"), n("expr-mul"), n("expr-add-tl"))] },
+  { name: "add-op", def: [t("+"), t("-")] },
+  { name: "expr-mul", def: [a(n("expr-unary"), n("expr-mul-tl"))] },
+  { name: "expr-mul-tl", def: [e, a(n("mul-op"), n("expr-unary"), n("expr-mul-tl"))] },
+  { name: "mul-op", def: [t("*"), t("/")] },
+  { name: "expr-unary", def: [n("expr-postfix"), a(t("!"), n("expr-unary"))] },
+  { name: "expr-postfix", def: [a(n("expr-hd"), n("expr-tl-list"))] },
   {
     name: "expr-hd",
     def: [
       n("string"),
       n("number"),
       a(t("("), n("expr-list"), t(")")),
-      a(n("big-ident"), n("kinded-args-opt"), t("{"), n("field-expr-list"), t("}")),
-      a(n("ident"), n("call-opt")),
+      a(t("["), n("expr-list"), t("]")),
+      a(n("name"), n("kinded-args-opt"), t("{"), n("field-expr-list"), n("base-opt"), t("}")),
+      a(n("name"), n("call-opt")),
       a(t("match"), n("expr"), t("{"), n("arm-list"), t("}")),
+      n("if-expr"),
+      a(t("return"), n("expr")),
       n("block"),
     ],
   },
+  { name: "if-expr", def: [a(t("if"), n("expr"), n("block"), n("else-opt"))] },
+  {
+    name: "else-opt",
+    def: [e, a(t("else"), n("block")), a(t("else"), n("if-expr"))],
+  },
   {
     name: "expr-tl-list",
     def: [e, a(t("."), n("ident"), n("call-opt"), n("expr-tl-list"))],
   },
   {
     name: "call-opt",
-    def: [e, a(n("kinded-args-opt"), t("("), n("expr-list"), t(")"))],
+    def: [
+      e,
+      a(t("["), n("kinded-list"), t("]")),
+      a(n("kinded-args-opt"), t("("), n("expr-list"), t(")")),
+    ],
   },
   {
     name: "expr-list",
@@ -147,8 +240,10 @@ const syntax: Grammar = verify(imported, exported, [
     def: [e, n("field-expr"), a(n("field-expr"), t(","), n("field-expr-list"))],
   },
   { name: "field-expr", def: [n("ident"), a(n("ident"), t(":"), n("expr"))] },
+  { name: "base-opt", def: [e, a(t(".."), n("expr"))] },
   { name: "arm-list", def: [e, a(n("arm"), n("arm-list"))] },
-  { name: "arm", def: [a(n("pat"), n("block"))] },
+  { name: "arm", def: [a(n("pat"), n("guard-opt"), n("block"))] },
+  { name: "guard-opt", def: [e, a(t("if"), n("expr"))] },
 ]);
 
 const special: string[] = [];
@@ -11,7 +11,10 @@ const tokens: Grammar = verify(new Set(), exported, [
     def: [e, a(n("big-ident-tl-one"), n("big-ident-tl"))],
   },
   { name: "big-ident-tl-one", def: [n("upper"), n("lower"), n("digit")] },
-  { name: "ident", def: [a(n("lower"), n("ident-tl"))] },
+  {
+    name: "ident",
+    def: [a(n("lower"), n("ident-tl")), a(t("_"), n("ident-tl-one"), n("ident-tl"))],
+  },
   { name: "ident-tl", def: [e, a(n("ident-tl-one"), n("ident-tl"))] },
   { name: "ident-tl-one", def: [n("lower"), n("digit"), t("_")] },
   { name: "string", def: [a(t('"'), n("string-inner"), t('"'))] },